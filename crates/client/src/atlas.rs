@@ -0,0 +1,79 @@
+use image::{imageops, DynamicImage, RgbaImage};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasRegion {
+    pub page: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Images packed into a few large pages so that draws of them can share a
+/// texture and be batched.
+#[derive(Default)]
+pub struct Atlas {
+    pub pages: Vec<RgbaImage>,
+    pub regions: Vec<(Arc<DynamicImage>, AtlasRegion)>,
+}
+
+impl Atlas {
+    pub const PAGE_SIZE: u32 = 2048;
+    // keeps neighbours from bleeding in when sampling at the edges
+    const PADDING: u32 = 1;
+
+    /// Shelf-packs `images`, tallest first. Images that don't fit on a page
+    /// are left out and keep their own texture.
+    pub fn pack<'a>(images: impl IntoIterator<Item = &'a Arc<DynamicImage>>) -> Self {
+        let mut seen = HashSet::new();
+        let mut images: Vec<&Arc<DynamicImage>> = images
+            .into_iter()
+            .filter(|image| {
+                image.width() > 0
+                    && image.height() > 0
+                    && image.width() + Self::PADDING <= Self::PAGE_SIZE
+                    && image.height() + Self::PADDING <= Self::PAGE_SIZE
+            })
+            .filter(|image| seen.insert(Arc::as_ptr(image)))
+            .collect();
+        images.sort_by_key(|image| Reverse((image.height(), image.width())));
+
+        let mut atlas = Self::default();
+        let (mut x, mut y, mut shelf) = (0, 0, 0);
+        for image in images {
+            let (width, height) = (image.width(), image.height());
+            if x + width > Self::PAGE_SIZE {
+                x = 0;
+                y += shelf;
+                shelf = 0;
+            }
+            if atlas.pages.is_empty() || y + height > Self::PAGE_SIZE {
+                atlas
+                    .pages
+                    .push(RgbaImage::new(Self::PAGE_SIZE, Self::PAGE_SIZE));
+                (x, y, shelf) = (0, 0, 0);
+            }
+            let page = atlas.pages.last_mut().unwrap();
+            match image.as_ref() {
+                DynamicImage::ImageRgba8(data) => imageops::replace(page, data, x as i64, y as i64),
+                _ => imageops::replace(page, &image.to_rgba8(), x as i64, y as i64),
+            }
+            atlas.regions.push((
+                image.clone(),
+                AtlasRegion {
+                    page: atlas.pages.len() - 1,
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+            ));
+            x += width + Self::PADDING;
+            shelf = shelf.max(height + Self::PADDING);
+        }
+        atlas
+    }
+}
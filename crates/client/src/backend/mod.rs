@@ -0,0 +1,220 @@
+use glam::{vec2, Vec2};
+use image::RgbaImage;
+use sdl_sys::{SDL_FRect, SDL_FlipMode};
+
+mod sdl;
+mod software;
+
+pub use self::sdl::SdlBackend;
+pub use software::SoftwareBackend;
+
+/// Handle to a texture owned by a [`Backend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureId(pub usize);
+
+impl TextureId {
+    /// Given for textures that failed to be created; draws nothing.
+    pub const INVALID: Self = Self(usize::MAX);
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// Regular alpha blending.
+    #[default]
+    Blend,
+    /// Adds the source on top, for glows and hit flashes.
+    Add,
+    /// Multiplies the destination by the source, for shadows and darkening.
+    Multiply,
+}
+
+/// One textured rect of a [`Backend::copy_batch`].
+#[derive(Debug, Clone, Copy)]
+pub struct Quad {
+    pub src: SDL_FRect,
+    pub dst: SDL_FRect,
+    /// RGB tint and alpha.
+    pub color: [u8; 4],
+    pub flip: SDL_FlipMode::Type,
+    /// Clockwise, in degrees, around `center`.
+    pub angle: f32,
+    /// Relative to the top left of `dst`.
+    pub center: Vec2,
+    pub blend: BlendMode,
+}
+
+impl Quad {
+    pub fn new(src: SDL_FRect, dst: SDL_FRect) -> Self {
+        Self {
+            src,
+            dst,
+            color: [255, 255, 255, 255],
+            flip: SDL_FlipMode::SDL_FLIP_NONE,
+            angle: 0.0,
+            center: Vec2::ZERO,
+            blend: BlendMode::Blend,
+        }
+    }
+
+    /// The corners of `dst` after rotation, clockwise from the top left.
+    pub fn corners(&self) -> [Vec2; 4] {
+        let SDL_FRect { x, y, w, h } = self.dst;
+        let pivot = vec2(x, y) + self.center;
+        let rotation = Vec2::from_angle(self.angle.to_radians());
+        [
+            vec2(x, y),
+            vec2(x + w, y),
+            vec2(x + w, y + h),
+            vec2(x, y + h),
+        ]
+        .map(|corner| pivot + rotation.rotate(corner - pivot))
+    }
+
+    /// The axis aligned rect covering the rotated quad.
+    pub fn bounds(&self) -> SDL_FRect {
+        if self.angle == 0.0 {
+            return self.dst;
+        }
+        let corners = self.corners();
+        let min = corners.into_iter().reduce(Vec2::min).unwrap();
+        let max = corners.into_iter().reduce(Vec2::max).unwrap();
+        SDL_FRect {
+            x: min.x,
+            y: min.y,
+            w: max.x - min.x,
+            h: max.y - min.y,
+        }
+    }
+}
+
+// takes the first freed slot, so ids stay small
+fn insert_texture<T>(textures: &mut Vec<Option<T>>, texture: T) -> TextureId {
+    match textures.iter().position(Option::is_none) {
+        Some(index) => {
+            textures[index] = Some(texture);
+            TextureId(index)
+        }
+        None => {
+            textures.push(Some(texture));
+            TextureId(textures.len() - 1)
+        }
+    }
+}
+
+/// What `sdl::Renderer` draws onto. Rects are in logical units, the backend
+/// applies its own pixel scale.
+pub trait Backend {
+    fn create_texture(&mut self, image: &RgbaImage) -> TextureId;
+
+    /// Frees `texture`; its id may be handed out again.
+    fn destroy_texture(&mut self, texture: TextureId);
+
+    fn copy(&mut self, texture: TextureId, quad: &Quad);
+
+    /// Draws many quads of one texture, in order. Backends that can should
+    /// submit them as a single draw call.
+    fn copy_batch(&mut self, texture: TextureId, quads: &[Quad]) {
+        for quad in quads {
+            self.copy(texture, quad);
+        }
+    }
+
+    /// Stretches only the middle of the `src` part of `texture` into `dst`,
+    /// keeping the `[left, right, top, bottom]` borders at their size.
+    fn copy_9grid(
+        &mut self,
+        texture: TextureId,
+        src: &SDL_FRect,
+        borders: [f32; 4],
+        dst: &SDL_FRect,
+        alpha: u8,
+    ) {
+        let [left, right, top, bottom] = borders;
+        let columns = [
+            (src.x, left, dst.x, left),
+            (
+                src.x + left,
+                src.w - left - right,
+                dst.x + left,
+                dst.w - left - right,
+            ),
+            (src.x + src.w - right, right, dst.x + dst.w - right, right),
+        ];
+        let rows = [
+            (src.y, top, dst.y, top),
+            (
+                src.y + top,
+                src.h - top - bottom,
+                dst.y + top,
+                dst.h - top - bottom,
+            ),
+            (
+                src.y + src.h - bottom,
+                bottom,
+                dst.y + dst.h - bottom,
+                bottom,
+            ),
+        ];
+        for (sy, sh, dy, dh) in rows {
+            for (sx, sw, dx, dw) in columns {
+                if sw <= 0.0 || sh <= 0.0 || dw <= 0.0 || dh <= 0.0 {
+                    continue;
+                }
+                let quad = Quad::new(
+                    SDL_FRect {
+                        x: sx,
+                        y: sy,
+                        w: sw,
+                        h: sh,
+                    },
+                    SDL_FRect {
+                        x: dx,
+                        y: dy,
+                        w: dw,
+                        h: dh,
+                    },
+                );
+                self.copy(
+                    texture,
+                    &Quad {
+                        color: [255, 255, 255, alpha],
+                        ..quad
+                    },
+                );
+            }
+        }
+    }
+
+    fn fill_rect(&mut self, rect: &SDL_FRect, color: [u8; 4]);
+
+    fn draw_rect(&mut self, rect: &SDL_FRect, color: [u8; 4]) {
+        let SDL_FRect { x, y, w, h } = *rect;
+        for edge in [
+            SDL_FRect { x, y, w, h: 1.0 },
+            SDL_FRect {
+                x,
+                y: y + h - 1.0,
+                w,
+                h: 1.0,
+            },
+            SDL_FRect { x, y, w: 1.0, h },
+            SDL_FRect {
+                x: x + w - 1.0,
+                y,
+                w: 1.0,
+                h,
+            },
+        ] {
+            self.fill_rect(&edge, color);
+        }
+    }
+
+    fn draw_line(&mut self, from: Vec2, to: Vec2, color: [u8; 4]);
+
+    fn clear(&mut self, color: [u8; 4]);
+
+    fn present(&mut self) {}
+
+    /// The pixels drawn so far, in physical pixels.
+    fn snapshot(&self) -> RgbaImage;
+}
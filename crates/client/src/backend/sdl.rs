@@ -0,0 +1,200 @@
+use glam::{vec2, Vec2};
+use image::RgbaImage;
+use sdl_sys::{Error, SDL_FColor, SDL_FPoint, SDL_FRect, SDL_FlipMode, SDL_Vertex};
+
+use super::{insert_texture, Backend, BlendMode, Quad, TextureId};
+
+fn blend_mode(mode: BlendMode) -> sdl_sys::SDL_BlendMode {
+    match mode {
+        BlendMode::Blend => sdl_sys::SDL_BLENDMODE_BLEND,
+        BlendMode::Add => sdl_sys::SDL_BLENDMODE_ADD,
+        BlendMode::Multiply => sdl_sys::SDL_BLENDMODE_MUL,
+    }
+}
+
+// failed calls are reported and what they'd draw skipped, rather than
+// ending the game
+fn report<T>(result: Result<T, Error>) -> Option<T> {
+    result.map_err(|err| eprintln!("{err}")).ok()
+}
+
+pub struct SdlBackend<'r> {
+    canvas: &'r sdl_sys::Renderer<'r>,
+    textures: Vec<Option<sdl_sys::Texture<'r>>>,
+}
+
+impl<'r> SdlBackend<'r> {
+    pub fn new(canvas: &'r sdl_sys::Renderer<'r>) -> Self {
+        Self {
+            canvas,
+            textures: vec![],
+        }
+    }
+
+    fn set_draw_color(&self, [r, g, b, a]: [u8; 4]) -> Result<(), Error> {
+        self.canvas
+            .set_draw_blend_mode(sdl_sys::SDL_BLENDMODE_BLEND)?;
+        self.canvas.set_draw_color(r, g, b, a)
+    }
+
+    fn texture(&self, texture: TextureId) -> Option<&sdl_sys::Texture<'r>> {
+        self.textures.get(texture.0)?.as_ref()
+    }
+
+    fn try_copy(&self, texture: &sdl_sys::Texture, quad: &Quad) -> Result<(), Error> {
+        let [r, g, b, a] = quad.color;
+        texture.set_alpha_mod(a)?;
+        texture.set_color_mod(r, g, b)?;
+        texture.set_blend_mode(blend_mode(quad.blend))?;
+        self.canvas.copy(
+            texture,
+            Some(&quad.src),
+            &quad.dst,
+            quad.angle as f64,
+            Some(&SDL_FPoint {
+                x: quad.center.x,
+                y: quad.center.y,
+            }),
+            quad.flip,
+        )
+    }
+
+    fn try_copy_batch(&self, texture: &sdl_sys::Texture, quads: &[Quad]) -> Result<(), Error> {
+        let size = vec2(texture.width() as f32, texture.height() as f32);
+        // the per-vertex colour carries tint and alpha instead
+        texture.set_alpha_mod(255)?;
+        texture.set_color_mod(255, 255, 255)?;
+        for quads in quads.chunk_by(|a, b| a.blend == b.blend) {
+            let mut vertices = Vec::with_capacity(quads.len() * 4);
+            let mut indices = Vec::with_capacity(quads.len() * 6);
+            for quad in quads {
+                let (mut u0, mut u1) = (quad.src.x / size.x, (quad.src.x + quad.src.w) / size.x);
+                let (mut v0, mut v1) = (quad.src.y / size.y, (quad.src.y + quad.src.h) / size.y);
+                if quad.flip == SDL_FlipMode::SDL_FLIP_HORIZONTAL {
+                    std::mem::swap(&mut u0, &mut u1);
+                }
+                if quad.flip == SDL_FlipMode::SDL_FLIP_VERTICAL {
+                    std::mem::swap(&mut v0, &mut v1);
+                }
+                let [r, g, b, a] = quad.color.map(|value| value as f32 / 255.0);
+                let color = SDL_FColor { r, g, b, a };
+                let base = vertices.len() as i32;
+                for (corner, (u, v)) in
+                    quad.corners()
+                        .into_iter()
+                        .zip([(u0, v0), (u1, v0), (u1, v1), (u0, v1)])
+                {
+                    vertices.push(SDL_Vertex {
+                        position: SDL_FPoint {
+                            x: corner.x,
+                            y: corner.y,
+                        },
+                        color,
+                        tex_coord: SDL_FPoint { x: u, y: v },
+                    });
+                }
+                indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+            }
+            texture.set_blend_mode(blend_mode(quads[0].blend))?;
+            self.canvas.geometry(Some(texture), &vertices, &indices)?;
+        }
+        Ok(())
+    }
+
+    fn try_copy_9grid(
+        &self,
+        texture: &sdl_sys::Texture,
+        src: &SDL_FRect,
+        [left, right, top, bottom]: [f32; 4],
+        dst: &SDL_FRect,
+        alpha: u8,
+    ) -> Result<(), Error> {
+        texture.set_alpha_mod(alpha)?;
+        texture.set_color_mod(255, 255, 255)?;
+        texture.set_blend_mode(sdl_sys::SDL_BLENDMODE_BLEND)?;
+        self.canvas
+            .copy_9grid(texture, Some(src), left, right, top, bottom, 1.0, dst)
+    }
+}
+
+impl Backend for SdlBackend<'_> {
+    fn create_texture(&mut self, image: &RgbaImage) -> TextureId {
+        let texture = self
+            .canvas
+            .create_texture(image.width(), image.height(), image);
+        match report(texture) {
+            Some(texture) => insert_texture(&mut self.textures, texture),
+            None => TextureId::INVALID,
+        }
+    }
+
+    fn destroy_texture(&mut self, texture: TextureId) {
+        if let Some(slot) = self.textures.get_mut(texture.0) {
+            *slot = None;
+        }
+    }
+
+    fn copy(&mut self, texture: TextureId, quad: &Quad) {
+        if let Some(texture) = self.texture(texture) {
+            report(self.try_copy(texture, quad));
+        }
+    }
+
+    fn copy_batch(&mut self, texture: TextureId, quads: &[Quad]) {
+        if let Some(texture) = self.texture(texture) {
+            report(self.try_copy_batch(texture, quads));
+        }
+    }
+
+    fn copy_9grid(
+        &mut self,
+        texture: TextureId,
+        src: &SDL_FRect,
+        borders: [f32; 4],
+        dst: &SDL_FRect,
+        alpha: u8,
+    ) {
+        if let Some(texture) = self.texture(texture) {
+            report(self.try_copy_9grid(texture, src, borders, dst, alpha));
+        }
+    }
+
+    fn fill_rect(&mut self, rect: &SDL_FRect, color: [u8; 4]) {
+        report(
+            self.set_draw_color(color)
+                .and_then(|_| self.canvas.fill_rect(rect)),
+        );
+    }
+
+    fn draw_rect(&mut self, rect: &SDL_FRect, color: [u8; 4]) {
+        report(
+            self.set_draw_color(color)
+                .and_then(|_| self.canvas.draw_rect(rect)),
+        );
+    }
+
+    fn draw_line(&mut self, from: Vec2, to: Vec2, color: [u8; 4]) {
+        report(
+            self.set_draw_color(color)
+                .and_then(|_| self.canvas.draw_line(from.x, from.y, to.x, to.y)),
+        );
+    }
+
+    fn clear(&mut self, [r, g, b, a]: [u8; 4]) {
+        report(
+            self.canvas
+                .set_draw_color(r, g, b, a)
+                .and_then(|_| self.canvas.clear()),
+        );
+    }
+
+    fn present(&mut self) {
+        report(self.canvas.present());
+    }
+
+    fn snapshot(&self) -> RgbaImage {
+        report(self.canvas.read_pixels())
+            .and_then(|(width, height, pixels)| RgbaImage::from_raw(width, height, pixels))
+            .unwrap_or_else(|| RgbaImage::new(0, 0))
+    }
+}
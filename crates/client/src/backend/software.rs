@@ -0,0 +1,156 @@
+use glam::{vec2, Vec2};
+use image::{Rgba, RgbaImage};
+use sdl_sys::{SDL_FRect, SDL_FlipMode};
+
+use super::{insert_texture, Backend, BlendMode, Quad, TextureId};
+
+// the same equations as SDL's blend modes, on straight alpha
+fn blend(dst: &mut Rgba<u8>, [r, g, b, a]: [u8; 4], mode: BlendMode) {
+    let alpha = a as f32 / 255.0;
+    let [dr, dg, db, da] = dst.0.map(|value| value as f32);
+    let channel = |src: u8, dst: f32| -> u8 {
+        let src = src as f32;
+        let value = match mode {
+            BlendMode::Blend => src * alpha + dst * (1.0 - alpha),
+            BlendMode::Add => src * alpha + dst,
+            BlendMode::Multiply => src * dst / 255.0 + dst * (1.0 - alpha),
+        };
+        value.round().clamp(0.0, 255.0) as u8
+    };
+    let out_alpha = match mode {
+        BlendMode::Blend => a as f32 + da * (1.0 - alpha),
+        BlendMode::Add | BlendMode::Multiply => da,
+    };
+    dst.0 = [
+        channel(r, dr),
+        channel(g, dg),
+        channel(b, db),
+        out_alpha.round() as u8,
+    ];
+}
+
+/// Rasterizes on the CPU into an image, for rendering without a display.
+pub struct SoftwareBackend {
+    scale: f32,
+    target: RgbaImage,
+    textures: Vec<Option<RgbaImage>>,
+}
+
+impl SoftwareBackend {
+    /// `width` and `height` are logical units, the target is `scale` times
+    /// larger like a window with that pixel density.
+    pub fn new(width: u32, height: u32, scale: f32) -> Self {
+        Self {
+            scale,
+            target: RgbaImage::new(
+                (width as f32 * scale).round() as u32,
+                (height as f32 * scale).round() as u32,
+            ),
+            textures: vec![],
+        }
+    }
+
+    // the physical pixel range a logical span covers, clipped to `max`
+    fn span(&self, start: f32, length: f32, max: u32) -> (u32, u32) {
+        let from = (start * self.scale).round().max(0.0);
+        let to = ((start + length) * self.scale).round().min(max as f32);
+        (from as u32, to.max(from) as u32)
+    }
+}
+
+impl Backend for SoftwareBackend {
+    fn create_texture(&mut self, image: &RgbaImage) -> TextureId {
+        insert_texture(&mut self.textures, image.clone())
+    }
+
+    fn destroy_texture(&mut self, texture: TextureId) {
+        self.textures[texture.0] = None;
+    }
+
+    fn copy(&mut self, texture: TextureId, quad: &Quad) {
+        let dst = quad.dst;
+        if dst.w <= 0.0 || dst.h <= 0.0 {
+            return;
+        }
+        let image = self.textures[texture.0].as_ref().unwrap();
+        let bounds = quad.bounds();
+        let (left, right) = self.span(bounds.x, bounds.w, self.target.width());
+        let (top, bottom) = self.span(bounds.y, bounds.h, self.target.height());
+        let pivot = vec2(dst.x, dst.y) + quad.center;
+        // maps target pixels back into the unrotated quad
+        let rotation = Vec2::from_angle(-quad.angle.to_radians());
+        let [tr, tg, tb, alpha] = quad.color.map(|value| value as u32);
+
+        for y in top..bottom {
+            for x in left..right {
+                // sample at pixel centers, nearest neighbour like the SDL textures
+                let point = (vec2(x as f32, y as f32) + 0.5) / self.scale;
+                let local = pivot + rotation.rotate(point - pivot);
+                let mut u = (local.x - dst.x) / dst.w;
+                let mut v = (local.y - dst.y) / dst.h;
+                if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) {
+                    continue;
+                }
+                if quad.flip == SDL_FlipMode::SDL_FLIP_HORIZONTAL {
+                    u = 1.0 - u;
+                }
+                if quad.flip == SDL_FlipMode::SDL_FLIP_VERTICAL {
+                    v = 1.0 - v;
+                }
+                let sx = (quad.src.x + u * quad.src.w).floor() as u32;
+                let sy = (quad.src.y + v * quad.src.h).floor() as u32;
+                if sx >= image.width() || sy >= image.height() {
+                    continue;
+                }
+                let [r, g, b, a] = image.get_pixel(sx, sy).0.map(|value| value as u32);
+                let a = (a * alpha / 255) as u8;
+                if a > 0 {
+                    let color = [
+                        (r * tr / 255) as u8,
+                        (g * tg / 255) as u8,
+                        (b * tb / 255) as u8,
+                        a,
+                    ];
+                    blend(self.target.get_pixel_mut(x, y), color, quad.blend);
+                }
+            }
+        }
+    }
+
+    fn fill_rect(&mut self, rect: &SDL_FRect, color: [u8; 4]) {
+        let (left, right) = self.span(rect.x, rect.w, self.target.width());
+        let (top, bottom) = self.span(rect.y, rect.h, self.target.height());
+        for y in top..bottom {
+            for x in left..right {
+                blend(self.target.get_pixel_mut(x, y), color, BlendMode::Blend);
+            }
+        }
+    }
+
+    fn draw_line(&mut self, from: Vec2, to: Vec2, color: [u8; 4]) {
+        let (from, to) = (from * self.scale, to * self.scale);
+        let steps = (to - from).abs().max_element().ceil().max(1.0) as u32;
+        for step in 0..=steps {
+            let point = from.lerp(to, step as f32 / steps as f32).floor();
+            if point.x >= 0.0
+                && point.y >= 0.0
+                && (point.x as u32) < self.target.width()
+                && (point.y as u32) < self.target.height()
+            {
+                blend(
+                    self.target.get_pixel_mut(point.x as u32, point.y as u32),
+                    color,
+                    BlendMode::Blend,
+                );
+            }
+        }
+    }
+
+    fn clear(&mut self, color: [u8; 4]) {
+        self.target.pixels_mut().for_each(|pixel| pixel.0 = color);
+    }
+
+    fn snapshot(&self) -> RgbaImage {
+        self.target.clone()
+    }
+}
@@ -0,0 +1,95 @@
+use client::draw_queue::DrawQueue;
+use client::ecs::{self, systems};
+use client::map::{Map, MapOverlay};
+use client::sdl::Renderer;
+use client::wz;
+use std::error::Error;
+
+const USAGE: &str = "usage: render_map <map id> [--scale <n>] [--out <file.png>] \
+[--footholds] [--portals] [--ladders] [--debug]";
+
+struct Args {
+    map: String,
+    scale: f32,
+    out: Option<String>,
+    overlay: MapOverlay,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    let mut map = None;
+    let mut scale = 1.0;
+    let mut out = None;
+    let mut overlay = MapOverlay::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scale" => {
+                scale = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .filter(|scale: &f32| *scale > 0.0)
+                    .ok_or("--scale expects a positive number")?;
+            }
+            "--out" => out = Some(args.next().ok_or("--out expects a path")?),
+            "--footholds" => overlay.footholds = true,
+            "--portals" => overlay.portals = true,
+            "--ladders" => overlay.ladders = true,
+            "--debug" => {
+                overlay = MapOverlay {
+                    footholds: true,
+                    portals: true,
+                    ladders: true,
+                }
+            }
+            _ if arg.starts_with("--") || map.is_some() => {
+                return Err(format!("unexpected argument `{arg}`"))
+            }
+            _ => map = Some(arg),
+        }
+    }
+    Ok(Args {
+        map: map.ok_or("missing map id")?,
+        scale,
+        out,
+        overlay,
+    })
+}
+
+/// Renders a whole map to a PNG, e.g. `render_map 002000000 --scale 0.5 --debug`.
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    let node = wz::resolve_base()?;
+    let mut map = Map::new(&node, &args.map)
+        .map_err(|err| format!("can't load map {}: {err:?}", args.map))?;
+    let (min, max) = map.bounds();
+    let size = (max - min).max(glam::Vec2::ONE);
+
+    let mut renderer = Renderer::offscreen(size.x as u32, size.y as u32, args.scale);
+    renderer.load_atlas(&map.atlas());
+    renderer.clear([0, 0, 0, 255]);
+    let mut queue = DrawQueue::default();
+    map.submit(&mut queue, min, size, 0.0);
+    let mut entities = hecs::World::new();
+    ecs::spawn_map(&mut entities, &map);
+    systems::submit(&entities, &map, &mut queue, min, 1.0);
+    queue.flush(&mut renderer);
+    map.draw_overlay(&mut renderer, min, args.overlay);
+
+    let out = args.out.unwrap_or_else(|| format!("{}.png", args.map));
+    let image = renderer.snapshot();
+    image.save(&out)?;
+    println!(
+        "{out}: {}x{}, {} draw calls",
+        image.width(),
+        image.height(),
+        renderer.draw_calls()
+    );
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use glam::Vec2;
 use image::DynamicImage;
-use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::sync::Arc;
 use wz_reader::WzNodeCast;
 
+use crate::mount::Mount;
 use crate::sprite::Sprite;
+use crate::timer::Timer;
 use crate::wz::Node;
 
 #[derive(Debug)]
@@ -21,28 +22,68 @@ impl From<Node> for AvatarFramePart {
         let frame = AvatarFramePart {
             origin: node.get("origin").into(),
             z: node.get("z").into(),
-            map: node.get("map").into(),
+            map: node.try_get("map").map(Into::into).unwrap_or_default(),
             image: node.into(),
         };
         frame
     }
 }
 
+/// Layer ordering from `zmap.img` plus the slots each layer occupies from
+/// `smap.img`.
 #[derive(Default)]
 pub struct ZMap {
     pub layers: HashMap<String, i32>,
+    pub slots: HashMap<String, String>,
 }
 
-impl From<Node> for ZMap {
-    fn from(node: Node) -> Self {
+impl ZMap {
+    pub fn new(zmap: Node, smap: Node) -> Self {
         Self {
-            layers: node
+            layers: zmap
                 .children()
                 .keys()
                 .rev()
                 .enumerate()
                 .map(|(index, item)| (item.to_string(), index as i32))
                 .collect(),
+            slots: smap.into(),
+        }
+    }
+
+    /// Rank of a `z` layer name; names missing from `zmap.img` go behind
+    /// everything unless they're one of the `*Front` layers.
+    pub fn rank(&self, z: &str) -> i32 {
+        match self.layers.get(z) {
+            Some(rank) => *rank,
+            None if z.ends_with("Front") => self.layers.len() as i32,
+            None => -1,
+        }
+    }
+}
+
+// slot strings like `vslot` are runs of two-letter codes, e.g. "CpH1H5"
+fn slot_codes(slots: &str) -> impl Iterator<Item = &str> {
+    (0..slots.len() / 2).map(move |i| &slots[i * 2..i * 2 + 2])
+}
+
+/// A frame that reuses another action's frame through its `action`/`frame`
+/// entries, optionally shifted by `move` and mirrored by `flip`.
+#[derive(Debug)]
+pub struct AvatarFrameRef {
+    pub action: String,
+    pub frame: usize,
+    pub offset: Vec2,
+    pub flip: bool,
+}
+
+impl From<Node> for AvatarFrameRef {
+    fn from(node: Node) -> Self {
+        Self {
+            action: node.get("action").into(),
+            frame: node.try_get("frame").map(i32::from).unwrap_or(0) as usize,
+            offset: node.try_get("move").map(Into::into).unwrap_or_default(),
+            flip: node.try_get("flip").map(Into::into).unwrap_or(false),
         }
     }
 }
@@ -51,6 +92,7 @@ impl From<Node> for ZMap {
 pub struct AvatarFrame {
     pub parts: HashMap<String, AvatarFramePart>,
     pub delay: Option<i32>,
+    pub reference: Option<AvatarFrameRef>,
 }
 
 impl From<Node> for AvatarFrame {
@@ -68,6 +110,11 @@ impl From<Node> for AvatarFrame {
                 })
                 .collect(),
             delay: node.try_get("delay").map(Into::into),
+            reference: if node.has("action") {
+                Some(node.into())
+            } else {
+                None
+            },
         }
     }
 }
@@ -83,37 +130,34 @@ impl From<Node> for AvatarPart {
         let info: AvatarPartInfo = node.get("info").into();
         Self {
             info,
-            variant: node
-                .children()
-                .into_iter()
-                .filter(|(key, _)| key.as_str() != "info")
-                .map(|(key, node)| {
-                    (key.to_string(), {
-                        let children = node.children();
-                        if children.contains_key("0") {
-                            children
-                                .into_iter()
-                                .filter_map(|(_, node)| {
-                                    if node.has("action") {
-                                        None
-                                    } else {
-                                        Some(node.into())
-                                    }
-                                })
-                                .collect()
-                        } else {
-                            vec![node.into()]
-                        }
-                    })
-                })
-                .collect(),
+            variant: Self::variants(&node),
         }
     }
 }
 
+impl AvatarPart {
+    pub fn variants(node: &Node) -> HashMap<String, Vec<AvatarFrame>> {
+        node.children()
+            .into_iter()
+            .filter(|(key, _)| key.as_str() != "info")
+            .map(|(key, node)| {
+                (key.to_string(), {
+                    let children = node.children();
+                    if children.contains_key("0") {
+                        children.into_iter().map(|(_, node)| node.into()).collect()
+                    } else {
+                        vec![node.into()]
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug)]
 pub struct AvatarPartInfo {
     pub slot: String,
+    pub vslot: String,
     pub cash: bool,
 }
 
@@ -121,134 +165,254 @@ impl From<Node> for AvatarPartInfo {
     fn from(value: Node) -> Self {
         Self {
             slot: value.get("islot").into(),
-            cash: value.get("cash").into(),
+            vslot: value.try_get("vslot").map(Into::into).unwrap_or_default(),
+            cash: value.try_get("cash").map(Into::into).unwrap_or(false),
         }
     }
 }
-#[derive(Debug, Default)]
-pub struct Timer {
-    elapsed: f32,
-    intervals: Vec<f32>,
-    total: f32,
-    pub index: usize,
-}
-
-impl Timer {
-    pub fn new(intervals: Vec<f32>) -> Self {
-        Self {
-            total: intervals.iter().sum(),
-            elapsed: 0.0,
-            intervals,
-            index: 0,
-        }
-    }
-
-    pub fn tick(&mut self, delta: f32) -> bool {
-        if self.intervals.is_empty() || self.total == 0.0 {
-            return false;
-        }
-        let prev = self.index;
-        self.elapsed += delta;
-        self.elapsed %= self.total;
-        while self.elapsed >= self.intervals[self.index] {
-            self.elapsed -= self.intervals[self.index];
-            self.index = (self.index + 1) % self.intervals.len();
-        }
-        self.index != prev
-    }
-}
 #[derive(Default)]
 pub struct Character {
     pub slots: HashMap<String, AvatarPart>,
     pub action: String,
     pub emotion: String,
+    pub mount: Option<Mount>,
+    // which equipped slot each `vslot` code belongs to
+    owners: HashMap<String, String>,
     timer: Timer,
     z_map: Arc<ZMap>,
 }
 
+/// The concrete body frame an action frame ends up drawing once all
+/// `action`/`frame` references have been followed.
+pub struct ResolvedFrame<'a> {
+    pub action: &'a str,
+    pub index: usize,
+    pub offset: Vec2,
+    pub flip: bool,
+}
+
 impl Character {
     pub fn new(parts: Vec<Node>, z_map: Arc<ZMap>) -> Self {
         let mut item = Self {
             slots: HashMap::new(),
             action: "stand1".to_string(),
             emotion: "default".to_string(),
+            mount: None,
+            owners: HashMap::new(),
             timer: Timer::new(vec![]),
             z_map,
         };
         for part in parts {
             item.insert(part);
         }
-        item.timer = Timer::new(
-            item.slots["Bd"].variant[&item.action]
-                .iter()
-                .map(|frame| frame.delay.unwrap() as f32)
-                .collect(),
-        );
+        item.timer = Timer::new(item.delays(&item.action));
         item
     }
 
     pub fn insert(&mut self, node: Node) {
         let part: AvatarPart = node.into();
         self.slots.insert(part.info.slot.clone(), part);
+        self.update_owners();
+    }
+
+    /// Base parts claim their slots first so equips covering them, like caps
+    /// over hair, take the codes over.
+    fn update_owners(&mut self) {
+        const BASE: [&str; 4] = ["Bd", "Hd", "Fc", "Hr"];
+        let mut slots: Vec<&AvatarPart> = self.slots.values().collect();
+        slots.sort_by_key(|part| {
+            (
+                !BASE.contains(&part.info.slot.as_str()),
+                part.info.slot.clone(),
+            )
+        });
+        self.owners = slots
+            .into_iter()
+            .flat_map(|part| {
+                slot_codes(&part.info.vslot).map(|code| (code.to_string(), part.info.slot.clone()))
+            })
+            .collect();
+    }
+
+    /// A layer is drawn only when every slot it occupies in `smap.img` is
+    /// owned by its own part, e.g. `hairOverHead` disappears under a cap whose
+    /// `vslot` covers the hair codes.
+    fn visible(&self, slot: &str, z: &str) -> bool {
+        let Some(codes) = self.z_map.slots.get(z) else {
+            return true;
+        };
+        slot_codes(codes).all(|code| self.owners.get(code).map_or(true, |owner| owner == slot))
     }
 
     pub fn tick(&mut self, delta: f32) -> bool {
+        if let Some(mount) = &mut self.mount {
+            mount.tick(delta);
+        }
         self.timer.tick(delta)
     }
 
+    /// While mounted the action drives the mount and the rider keeps
+    /// [`Mount::RIDING_ACTION`].
     pub fn set_action(&mut self, action: &str) {
+        let action = match &mut self.mount {
+            Some(mount) => {
+                mount.set_action(action);
+                Mount::RIDING_ACTION
+            }
+            None => action,
+        };
         self.action = action.to_string();
-        self.timer = Timer::new(
-            self.slots["Bd"].variant[&self.action]
-                .iter()
-                .map(|frame| frame.delay.unwrap() as f32)
-                .collect(),
-        );
+        self.timer = Timer::new(self.delays(&self.action));
+    }
+
+    pub fn set_mount(&mut self, mount: Option<Mount>) {
+        self.mount = mount;
+        self.set_action("stand1");
+    }
+
+    fn delays(&self, action: &str) -> Vec<f32> {
+        self.slots["Bd"].variant[action]
+            .iter()
+            .enumerate()
+            .map(|(index, frame)| {
+                frame
+                    .delay
+                    .or_else(|| {
+                        // dangling references get the default
+                        let resolved = self.resolve(action, index);
+                        self.slots["Bd"]
+                            .variant
+                            .get(resolved.action)?
+                            .get(resolved.index)?
+                            .delay
+                    })
+                    .unwrap_or(100) as f32
+            })
+            .collect()
+    }
+
+    /// Follows body frame references, accumulating `move` offsets and
+    /// toggling `flip` along the way.
+    pub fn resolve<'a>(&'a self, action: &'a str, index: usize) -> ResolvedFrame<'a> {
+        let body = &self.slots["Bd"];
+        let mut resolved = ResolvedFrame {
+            action,
+            index,
+            offset: Vec2::ZERO,
+            flip: false,
+        };
+        // guard against reference cycles in malformed data
+        for _ in 0..8 {
+            let Some(reference) = body
+                .variant
+                .get(resolved.action)
+                .and_then(|frames| frames.get(resolved.index))
+                .and_then(|frame| frame.reference.as_ref())
+            else {
+                break;
+            };
+            resolved.action = &reference.action;
+            resolved.index = reference.frame;
+            resolved.offset += reference.offset;
+            resolved.flip ^= reference.flip;
+        }
+        resolved
+    }
+
+    pub fn flip(&self) -> bool {
+        self.resolve(&self.action, self.timer.index).flip
     }
 
     pub fn frame(&self) -> Vec<Sprite> {
-        let action = &self.action;
         let emotion = &self.emotion;
-        let index = self.timer.index;
-
-        let body = &self.slots["Bd"].variant[action][index].parts["body"].map;
-        let head = &self.slots["Hd"].variant[action][index].parts["head"].map;
-        let offset = |slot: &str, part: &str, item: &HashMap<String, Vec2>| match slot {
-            "Bd" => match part {
-                "body" => Vec2::ZERO,
-                _ => item["navel"] - body["navel"],
-            },
-            "Hd" => item["neck"] - body["neck"],
-            "Fc" | "Hr" => item["brow"] - head["brow"] + head["neck"] - body["neck"],
-            _ => item["navel"] - body["navel"],
+        let ResolvedFrame {
+            action,
+            index,
+            offset: movement,
+            ..
+        } = self.resolve(&self.action, self.timer.index);
+
+        let map = |slot: &str, part: &str| {
+            let frame = self.slots.get(slot)?.variant.get(action)?.get(index)?;
+            Some(&frame.parts.get(part)?.map)
+        };
+        // the other parts hang off the body, so without it there's nothing
+        let Some(body) = map("Bd", "body") else {
+            return vec![];
+        };
+        let head = map("Hd", "head");
+        // seat the rider on the mount's navel
+        let movement = movement
+            + self
+                .mount
+                .as_ref()
+                .and_then(Mount::navel)
+                .zip(body.get("navel"))
+                .map(|(navel, body)| navel - *body)
+                .unwrap_or_default();
+        // parts missing what they attach by are left out
+        let offset = |slot: &str, part: &str, item: &HashMap<String, Vec2>| {
+            let point = |map: &HashMap<String, Vec2>, name: &str| map.get(name).copied();
+            Some(match slot {
+                "Bd" => match part {
+                    "body" => Vec2::ZERO,
+                    _ => point(item, "navel")? - point(body, "navel")?,
+                },
+                "Hd" => point(item, "neck")? - point(body, "neck")?,
+                "Fc" | "Hr" => {
+                    let head = head?;
+                    point(item, "brow")? - point(head, "brow")? + point(head, "neck")?
+                        - point(body, "neck")?
+                }
+                _ => point(item, "navel")? - point(body, "navel")?,
+            })
         };
 
         let mut frame = Vec::<Sprite>::new();
 
         for (slot, item) in self.slots.iter() {
-            let parts = if slot == "Fc" {
-                item.variant[emotion][0].borrow()
+            let variant = if slot == "Fc" {
+                item.variant.get(emotion).and_then(|frames| frames.first())
             } else {
-                item.variant[action][index].borrow()
+                item.variant
+                    .get(action)
+                    .and_then(|frames| frames.get(index))
+            };
+            let Some(variant) = variant else {
+                continue;
+            };
+            for (part, item) in variant.parts.iter() {
+                if !self.visible(slot, &item.z) {
+                    continue;
+                }
+                let Some(offset) = offset(slot, part, &item.map) else {
+                    continue;
+                };
+                frame.push(self.sprite(item, item.origin + offset - movement));
             }
-            .parts
-            .iter();
-            for (part, item) in parts {
-                frame.push(Sprite {
-                    a0: 0,
-                    a1: 0,
-                    alpha: 255,
-                    path: "".to_string(),
-                    image: item.image.clone(),
-                    origin: item.origin + offset(&slot, &part, &item.map),
-                    z: self.z_map.layers[&item.z],
-                    delay: 0,
-                    size: Vec2::new(item.image.width() as f32, item.image.height() as f32),
-                })
+        }
+
+        if let Some(mount) = &self.mount {
+            for item in mount.frames().flat_map(|frame| frame.parts.values()) {
+                frame.push(self.sprite(item, item.origin));
             }
         }
 
         frame.sort_by_key(|item| item.z);
         frame
     }
+
+    fn sprite(&self, item: &AvatarFramePart, origin: Vec2) -> Sprite {
+        Sprite {
+            a0: 255,
+            a1: 255,
+            alpha: 255,
+            path: "".to_string(),
+            image: item.image.clone(),
+            origin,
+            z: self.z_map.rank(&item.z),
+            delay: 0,
+            size: Vec2::new(item.image.width() as f32, item.image.height() as f32),
+        }
+    }
 }
@@ -0,0 +1,114 @@
+use glam::{vec2, Vec2};
+use sdl_sys::{Error, SDL_RendererLogicalPresentation, SDL_ScaleMode, Window};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Resolution {
+    pub const PRESETS: [Resolution; 3] = [
+        Resolution::new(800, 600),
+        Resolution::new(1024, 768),
+        Resolution::new(1366, 768),
+    ];
+
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// The preset after this one, the first for custom sizes.
+    pub fn next(self) -> Self {
+        let index = Self::PRESETS.iter().position(|item| *item == self);
+        Self::PRESETS[index.map_or(0, |index| (index + 1) % Self::PRESETS.len())]
+    }
+
+    pub fn size(self) -> Vec2 {
+        vec2(self.width as f32, self.height as f32)
+    }
+}
+
+impl Default for Resolution {
+    fn default() -> Self {
+        Self::PRESETS[0]
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    /// A window without decorations.
+    Borderless,
+    /// Covers the display, keeping the desktop mode.
+    Fullscreen,
+}
+
+/// How the resolution is fitted to the window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Scaling {
+    /// No fitting, the view grows with the window.
+    Native,
+    /// Whole multiples of the resolution only, with black bars around.
+    Integer,
+    /// As large as fits while keeping the aspect ratio.
+    #[default]
+    Letterbox,
+    Stretch,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DisplaySettings {
+    pub resolution: Resolution,
+    pub mode: WindowMode,
+    pub scaling: Scaling,
+}
+
+impl DisplaySettings {
+    /// Sets up the window and the renderer's logical presentation, to be
+    /// followed by [`DisplaySettings::fit`].
+    pub fn apply(&self, window: &Window, canvas: &sdl_sys::Renderer) -> Result<(), Error> {
+        let Resolution { width, height } = self.resolution;
+        window.set_fullscreen(self.mode == WindowMode::Fullscreen)?;
+        window.set_bordered(self.mode == WindowMode::Windowed)?;
+        if self.mode != WindowMode::Fullscreen {
+            window.set_size(width as i32, height as i32)?;
+        }
+        let presentation = match self.scaling {
+            Scaling::Native => SDL_RendererLogicalPresentation::SDL_LOGICAL_PRESENTATION_DISABLED,
+            Scaling::Integer => {
+                SDL_RendererLogicalPresentation::SDL_LOGICAL_PRESENTATION_INTEGER_SCALE
+            }
+            Scaling::Letterbox => {
+                SDL_RendererLogicalPresentation::SDL_LOGICAL_PRESENTATION_LETTERBOX
+            }
+            Scaling::Stretch => SDL_RendererLogicalPresentation::SDL_LOGICAL_PRESENTATION_STRETCH,
+        };
+        canvas.set_logical_presentation(
+            width as i32,
+            height as i32,
+            presentation,
+            SDL_ScaleMode::SDL_SCALEMODE_NEAREST,
+        )
+    }
+
+    /// Matches the renderer's scale to the window, again whenever its size or
+    /// pixel density changes. Returns the view size in logical units and the
+    /// pixels per unit text should be rasterized at.
+    pub fn fit(&self, window: &Window, canvas: &sdl_sys::Renderer) -> Result<(Vec2, f32), Error> {
+        match self.scaling {
+            Scaling::Native => {
+                let (width, height) = window.size();
+                let dpr = window.pixel_density();
+                canvas.set_scale(dpr, dpr)?;
+                Ok((vec2(width as f32, height as f32), dpr))
+            }
+            // the logical target has exactly the resolution's pixels
+            _ => {
+                canvas.set_scale(1.0, 1.0)?;
+                Ok((self.resolution.size(), 1.0))
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use glam::Vec2;
+use image::DynamicImage;
+use std::sync::Arc;
+
+use crate::sdl::{DrawParams, Renderer};
+use crate::sprite::Sprite;
+
+/// Sort key of a queued draw: map layer first, then `z` within the layer,
+/// then submission order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DrawKey {
+    pub layer: i32,
+    pub z: i32,
+    pub order: u32,
+}
+
+pub struct DrawCommand {
+    pub image: Arc<DynamicImage>,
+    pub origin: Vec2,
+    pub position: Vec2,
+    pub params: DrawParams,
+}
+
+/// Collects a frame's sprites and draws them sorted by [`DrawKey`], so that
+/// characters end up inside the map layer of the foothold they stand on.
+#[derive(Default)]
+pub struct DrawQueue {
+    items: Vec<(DrawKey, DrawCommand)>,
+    order: u32,
+}
+
+impl DrawQueue {
+    /// Backgrounds behind every map layer.
+    pub const BACKGROUND: i32 = -1;
+    /// Front backgrounds, over all map layers.
+    pub const FOREGROUND: i32 = 100;
+    /// Hit effects and damage, over everything of the map.
+    pub const EFFECT: i32 = 200;
+
+    /// `z` of portals within a layer, above its tiles and objects.
+    pub const PORTAL_Z: i32 = i32::MAX - 1;
+    /// `z` of characters within a layer, above everything else on it.
+    pub const LIFE_Z: i32 = i32::MAX;
+
+    pub fn push(&mut self, layer: i32, z: i32, command: DrawCommand) {
+        let key = DrawKey {
+            layer,
+            z,
+            order: self.order,
+        };
+        self.order += 1;
+        self.items.push((key, command));
+    }
+
+    pub fn sprite(&mut self, layer: i32, z: i32, sprite: &Sprite, position: Vec2, flip: bool) {
+        let params = DrawParams {
+            alpha: sprite.alpha,
+            flip,
+            ..Default::default()
+        };
+        self.sprite_with(layer, z, sprite, position, params);
+    }
+
+    pub fn sprite_with(
+        &mut self,
+        layer: i32,
+        z: i32,
+        sprite: &Sprite,
+        position: Vec2,
+        params: DrawParams,
+    ) {
+        self.push(
+            layer,
+            z,
+            DrawCommand {
+                image: sprite.image.clone(),
+                origin: sprite.origin,
+                position,
+                params,
+            },
+        );
+    }
+
+    /// Draws everything queued this frame in key order and empties the queue.
+    pub fn flush(&mut self, renderer: &mut Renderer) {
+        self.items.sort_unstable_by_key(|(key, _)| *key);
+        for (_, item) in self.items.drain(..) {
+            renderer.draw_image(&item.image, item.origin, item.position, item.params);
+        }
+        self.order = 0;
+    }
+}
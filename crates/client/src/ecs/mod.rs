@@ -0,0 +1,297 @@
+use glam::{vec2, Vec2};
+use hecs::{Entity, World};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::character::Character;
+use crate::map::Map;
+use crate::overhead::ChatBalloon;
+use crate::pet::Pet;
+use crate::random::Random;
+use crate::sprite::{Sprite, SpriteAnimation};
+use crate::timer::{Playback, Timer};
+
+pub mod systems;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Transform {
+    pub position: Vec2,
+    // position before the last simulation step, to interpolate from
+    pub prev_position: Vec2,
+    pub flip: bool,
+}
+
+impl Transform {
+    pub fn new(position: Vec2, flip: bool) -> Self {
+        Self {
+            position,
+            prev_position: position,
+            flip,
+        }
+    }
+
+    /// Position `alpha` of a step past the last one.
+    pub fn interpolate(&self, alpha: f32) -> Vec2 {
+        self.prev_position.lerp(self.position, alpha)
+    }
+}
+
+/// Frames of every action, shared by all entities of a kind, and the one
+/// playing.
+pub struct Animation {
+    pub actions: Arc<HashMap<String, SpriteAnimation>>,
+    pub action: String,
+    pub timer: Timer,
+}
+
+impl Animation {
+    pub fn new(actions: Arc<HashMap<String, SpriteAnimation>>, action: &str) -> Self {
+        let mut animation = Self {
+            actions,
+            action: String::new(),
+            timer: Timer::default(),
+        };
+        animation.set_action(action);
+        animation
+    }
+
+    /// Restarts on `action`, keeping the current one if there's no such
+    /// action.
+    pub fn set_action(&mut self, action: &str) {
+        if self.action == action {
+            return;
+        }
+        let Some(animation) = self.actions.get(action) else {
+            return;
+        };
+        self.action = action.to_string();
+        self.timer = Timer::with_playback(
+            animation
+                .frames
+                .iter()
+                .map(|frame| frame.delay as f32)
+                .collect(),
+            animation.timer.playback,
+        );
+    }
+
+    /// Plays `action` through once, stopping on its last frame.
+    pub fn play_once(&mut self, action: &str) {
+        self.set_action(action);
+        self.timer.playback = Playback::Once;
+    }
+
+    pub fn frame(&self) -> Option<&Sprite> {
+        self.actions.get(&self.action)?.frames.get(self.timer.index)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Physics {
+    /// px per ms.
+    pub velocity: Vec2,
+    /// Falls and walks along footholds, flies freely otherwise.
+    pub gravity: bool,
+    pub grounded: bool,
+}
+
+/// The foothold an entity stands on, deciding the map layer it's drawn in.
+#[derive(Debug, Clone, Copy)]
+pub struct FootholdAttachment {
+    pub foothold: i32,
+}
+
+pub struct Npc {
+    pub id: String,
+    /// Loops of `stand` played so far, see [`systems::npc_actions`].
+    pub loops: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MobState {
+    Stand,
+    Move { direction: f32 },
+}
+
+/// Which owner a [`Pet`] follows, and how far back in line it walks.
+pub struct PetSlot {
+    pub owner: Entity,
+    pub index: usize,
+}
+
+pub struct Mob {
+    pub id: String,
+    // px per ms
+    pub speed: f32,
+    // dealt on touch
+    pub damage: u32,
+    // walking range
+    pub rx0: f32,
+    pub rx1: f32,
+    pub state: MobState,
+    // ms until the next decision
+    pub cooldown: f32,
+    pub random: Random,
+}
+
+pub struct Portal {
+    // pn
+    pub name: String,
+    // pt
+    pub kind: i32,
+}
+
+pub struct Player {
+    pub name: String,
+    pub avatar: Character,
+    pub balloon: Option<ChatBalloon>,
+    /// px per ms.
+    pub speed: f32,
+    /// ms left of not taking damage after a hit.
+    pub invincible: f32,
+}
+
+/// Level, job and gauges, shown on the status bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub level: u32,
+    pub job: u32,
+    pub hp: u32,
+    pub max_hp: u32,
+    pub mp: u32,
+    pub max_mp: u32,
+    pub exp: u64,
+    /// Exp to the next level.
+    pub max_exp: u64,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            job: 0,
+            hp: 50,
+            max_hp: 50,
+            mp: 5,
+            max_mp: 5,
+            exp: 0,
+            max_exp: 15,
+        }
+    }
+}
+
+impl Stats {
+    /// The job's branch, which is enough for the status bar.
+    pub fn job_name(&self) -> &'static str {
+        match self.job / 100 {
+            0 => "Beginner",
+            1 => "Warrior",
+            2 => "Magician",
+            3 => "Bowman",
+            4 => "Thief",
+            5 => "Pirate",
+            _ => "Adventurer",
+        }
+    }
+}
+
+/// Spawns the portals, NPCs and mobs of `map`.
+pub fn spawn_map(world: &mut World, map: &Map) {
+    for item in &map.portals {
+        world.spawn((
+            Transform::new(item.position, false),
+            Portal {
+                name: item.pn.clone(),
+                kind: item.pt,
+            },
+        ));
+    }
+
+    for (index, item) in map.life.iter().enumerate() {
+        let transform = Transform::new(vec2(item.x as f32, item.cy as f32), item.f == 1);
+        let attachment = FootholdAttachment { foothold: item.fh };
+        match item.r#type.as_str() {
+            "n" => {
+                let Some(npc) = map.npc.get(&item.id) else {
+                    continue;
+                };
+                world.spawn((
+                    transform,
+                    attachment,
+                    Animation::new(npc.actions.clone(), "stand"),
+                    Npc {
+                        id: item.id.clone(),
+                        loops: 0,
+                    },
+                ));
+            }
+            "m" => {
+                let Some(mob) = map.mob.get(&item.id) else {
+                    continue;
+                };
+                // so mobs of a kind don't all move in step
+                let mut random = Random::seeded(&format!("{}{index}", item.id));
+                let mut animation = Animation::new(mob.actions.clone(), "stand");
+                animation
+                    .timer
+                    .seek(random.range(0.0, animation.timer.duration()));
+                world.spawn((
+                    transform,
+                    attachment,
+                    animation,
+                    Physics {
+                        gravity: true,
+                        grounded: true,
+                        ..Default::default()
+                    },
+                    Mob {
+                        id: item.id.clone(),
+                        speed: mob.speed(),
+                        damage: mob.touch_damage(),
+                        rx0: item.rx0 as f32,
+                        rx1: item.rx1 as f32,
+                        state: MobState::Stand,
+                        cooldown: 0.0,
+                        random,
+                    },
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Spawns a player flying freely at `position`.
+pub fn spawn_player(world: &mut World, map: &Map, player: Player, position: Vec2) -> Entity {
+    let foothold = map
+        .foothold_below(position - vec2(0.0, 1.0))
+        .map_or(0, |(id, _)| id);
+    world.spawn((
+        Transform::new(position, false),
+        Physics::default(),
+        FootholdAttachment { foothold },
+        Stats::default(),
+        player,
+    ))
+}
+
+/// Spawns `pet` next to `owner`, returning it back if `owner` already has
+/// [`Pet::MAX`] pets out.
+pub fn spawn_pet(world: &mut World, owner: Entity, pet: Pet) -> Result<Entity, Pet> {
+    let index = world
+        .query_mut::<&PetSlot>()
+        .into_iter()
+        .filter(|(_, slot)| slot.owner == owner)
+        .count();
+    if index >= Pet::MAX {
+        return Err(pet);
+    }
+    let position = world
+        .get::<&Transform>(owner)
+        .map_or(Vec2::ZERO, |transform| transform.position);
+    Ok(world.spawn((
+        Transform::new(position, false),
+        pet,
+        PetSlot { owner, index },
+    )))
+}
@@ -0,0 +1,323 @@
+use glam::{vec2, Vec2};
+use hecs::{Entity, World};
+use std::collections::HashMap;
+
+use super::{
+    Animation, FootholdAttachment, Mob, MobState, Npc, PetSlot, Physics, Player, Portal, Stats,
+    Transform,
+};
+use crate::draw_queue::DrawQueue;
+use crate::map::Map;
+use crate::mount::Mount;
+use crate::overhead::{ChatBalloonStyle, NameTag};
+use crate::pet::Pet;
+use crate::sdl::{DrawParams, Renderer};
+use crate::timer::TimerEvent;
+
+// px per ms², as for pets
+const GRAVITY: f32 = 0.002;
+const MAX_FALL: f32 = 0.67;
+// highest step walked up or down without falling
+const STEP_HEIGHT: f32 = 5.0;
+
+/// Advances animations, avatars and chat balloons by `delta` ms.
+pub fn animate(world: &mut World, map: &mut Map, delta: f32) {
+    for (_, animation) in world.query_mut::<&mut Animation>() {
+        animation.timer.tick(delta);
+    }
+    for (_, pet) in world.query_mut::<&mut Pet>() {
+        pet.animate(delta);
+    }
+    for (_, player) in world.query_mut::<&mut Player>() {
+        player.avatar.tick(delta);
+        if let Some(balloon) = &mut player.balloon {
+            if !balloon.tick(delta) {
+                player.balloon = None;
+            }
+        }
+    }
+    map.portal_timer.tick(delta);
+}
+
+/// NPCs stand, playing one of their other actions, like `say` or `eye`,
+/// through once every few loops of `stand`.
+pub fn npc_actions(world: &mut World) {
+    const LOOPS: usize = 3;
+    for (_, (npc, animation)) in world.query_mut::<(&mut Npc, &mut Animation)>() {
+        let events = animation.timer.events();
+        if events.contains(&TimerEvent::Finished) {
+            animation.set_action("stand");
+            continue;
+        }
+        if animation.action != "stand" || !events.contains(&TimerEvent::Looped) {
+            continue;
+        }
+        npc.loops += 1;
+        if npc.loops % LOOPS != 0 {
+            continue;
+        }
+        let mut others: Vec<&String> = animation
+            .actions
+            .keys()
+            .filter(|action| *action != "stand")
+            .collect();
+        if others.is_empty() {
+            continue;
+        }
+        // taking turns through them
+        others.sort();
+        let action = others[(npc.loops / LOOPS - 1) % others.len()].clone();
+        animation.play_once(&action);
+    }
+}
+
+/// Steers the players along `direction`, from the arrow keys.
+pub fn control(world: &mut World, direction: Vec2) {
+    for (_, (player, transform, physics)) in
+        world.query_mut::<(&mut Player, &mut Transform, &mut Physics)>()
+    {
+        let walking = physics.velocity.x != 0.0;
+        let speed = player.speed * player.avatar.mount.as_ref().map_or(1.0, Mount::speed_scale);
+        physics.velocity = direction * speed;
+        if direction.x != 0.0 {
+            transform.flip = direction.x > 0.0;
+        }
+        if !walking && direction.x != 0.0 {
+            player.avatar.set_action("walk1");
+        } else if walking && direction.x == 0.0 {
+            player.avatar.set_action("stand1");
+        }
+    }
+}
+
+/// Mobs stand around and wander within their range, one step of `step` ms.
+pub fn ai(world: &mut World, step: f32) {
+    for (_, (mob, transform, physics, animation)) in
+        world.query_mut::<(&mut Mob, &mut Transform, &mut Physics, &mut Animation)>()
+    {
+        mob.cooldown -= step;
+        if mob.cooldown <= 0.0 {
+            mob.cooldown = mob.random.range(1500.0, 4000.0);
+            mob.state = match mob.random.next() % 3 {
+                0 => MobState::Stand,
+                1 => MobState::Move { direction: -1.0 },
+                _ => MobState::Move { direction: 1.0 },
+            };
+        }
+        if let MobState::Move { direction } = &mut mob.state {
+            let x = transform.position.x;
+            if x <= mob.rx0 && *direction < 0.0 || x >= mob.rx1 && *direction > 0.0 {
+                *direction = -*direction;
+            }
+        }
+        match mob.state {
+            MobState::Move { direction }
+                if mob.speed > 0.0
+                    && physics.grounded
+                    && animation.actions.contains_key("move") =>
+            {
+                physics.velocity.x = direction * mob.speed;
+                // mobs face left unless flipped
+                transform.flip = direction > 0.0;
+                animation.set_action("move");
+            }
+            _ => {
+                physics.velocity.x = 0.0;
+                animation.set_action("stand");
+            }
+        }
+    }
+}
+
+/// Pets trail behind their owners, each at its own distance, one step of
+/// `step` ms.
+pub fn pets(world: &mut World, map: &Map, step: f32) {
+    let owners: HashMap<Entity, (Vec2, bool)> = world
+        .query::<(&Transform, &Player)>()
+        .iter()
+        .map(|(entity, (transform, _))| (entity, (transform.position, transform.flip)))
+        .collect();
+    for (_, (transform, pet, slot)) in world.query_mut::<(&mut Transform, &mut Pet, &PetSlot)>() {
+        let Some(&(owner, flip)) = owners.get(&slot.owner) else {
+            continue;
+        };
+        let behind = if flip { -1.0 } else { 1.0 };
+        let target = owner + vec2(behind * (40.0 + 35.0 * slot.index as f32), 0.0);
+        pet.update(transform, step, target, map);
+    }
+}
+
+/// Players running into mobs take their touch damage, then can't be hurt
+/// for a while. Returns the damage dealt and where, over the players' heads.
+pub fn touch_damage(world: &mut World, step: f32) -> Vec<(u32, Vec2)> {
+    const INVINCIBLE: f32 = 2000.0;
+    // around the feet, roughly the avatar's size
+    const PLAYER: Vec2 = vec2(30.0, 60.0);
+    let mobs: Vec<(Vec2, Vec2, u32)> = world
+        .query::<(&Transform, &Animation, &Mob)>()
+        .iter()
+        .filter(|(_, (_, _, mob))| mob.damage > 0)
+        .filter_map(|(_, (transform, animation, mob))| {
+            let sprite = animation.frame()?;
+            let origin = if transform.flip {
+                vec2(sprite.size.x - sprite.origin.x, sprite.origin.y)
+            } else {
+                sprite.origin
+            };
+            let lt = transform.position - origin;
+            Some((lt, lt + sprite.size, mob.damage))
+        })
+        .collect();
+
+    let mut hits = vec![];
+    for (_, (transform, player, stats)) in
+        world.query_mut::<(&Transform, &mut Player, &mut Stats)>()
+    {
+        player.invincible = (player.invincible - step).max(0.0);
+        if player.invincible > 0.0 {
+            continue;
+        }
+        let lt = transform.position - vec2(PLAYER.x / 2.0, PLAYER.y);
+        let rb = lt + PLAYER;
+        let hit = mobs
+            .iter()
+            .find(|(mob_lt, mob_rb, _)| lt.cmplt(*mob_rb).all() && mob_lt.cmplt(rb).all());
+        if let Some(&(_, _, damage)) = hit {
+            stats.hp = stats.hp.saturating_sub(damage);
+            player.invincible = INVINCIBLE;
+            hits.push((damage, transform.position - vec2(0.0, PLAYER.y)));
+        }
+    }
+    hits
+}
+
+/// Moves everything with [`Physics`] by one step of `step` ms, keeping
+/// walkers on their footholds.
+pub fn physics(world: &mut World, map: &Map, step: f32) {
+    for (_, (transform, physics, attachment)) in
+        world.query_mut::<(&mut Transform, &mut Physics, &mut FootholdAttachment)>()
+    {
+        transform.prev_position = transform.position;
+        if !physics.gravity {
+            transform.position += physics.velocity * step;
+            if let Some((foothold, _)) = map.foothold_below(transform.position - vec2(0.0, 1.0)) {
+                attachment.foothold = foothold;
+            }
+            continue;
+        }
+
+        if !physics.grounded {
+            physics.velocity.y = (physics.velocity.y + GRAVITY * step).min(MAX_FALL);
+        }
+        let next = transform.position + physics.velocity * step;
+        match map.foothold_below(vec2(next.x, transform.position.y - STEP_HEIGHT)) {
+            Some((foothold, y))
+                if physics.grounded && y - next.y <= STEP_HEIGHT
+                    || physics.velocity.y >= 0.0 && next.y >= y =>
+            {
+                transform.position = vec2(next.x, y);
+                physics.velocity.y = 0.0;
+                physics.grounded = true;
+                attachment.foothold = foothold;
+            }
+            // walked off an edge or in the air
+            _ => {
+                transform.position = next;
+                physics.grounded = false;
+            }
+        }
+    }
+}
+
+/// Queues portals, NPCs, mobs, pets and players on the map layers of their
+/// footholds, `alpha` of a step past the last one.
+pub fn submit(world: &World, map: &Map, queue: &mut DrawQueue, camera: Vec2, alpha: f32) {
+    let top = map.top_layer();
+    if let Some(sprite) = map.helper.pv.get(map.portal_timer.index) {
+        for (_, (transform, portal)) in world.query::<(&Transform, &Portal)>().iter() {
+            // spawn points
+            if portal.name == "sp" {
+                continue;
+            }
+            queue.sprite(
+                top,
+                DrawQueue::PORTAL_Z,
+                sprite,
+                transform.position - camera,
+                false,
+            );
+        }
+    }
+
+    for (_, (transform, animation, attachment)) in world
+        .query::<(&Transform, &Animation, Option<&FootholdAttachment>)>()
+        .iter()
+    {
+        let Some(sprite) = animation.frame() else {
+            continue;
+        };
+        let layer = attachment.map_or(top, |item| map.layer_of(item.foothold));
+        let params = DrawParams {
+            alpha: sprite.alpha_at(animation.timer.progress()),
+            flip: transform.flip,
+            ..Default::default()
+        };
+        queue.sprite_with(
+            layer,
+            DrawQueue::LIFE_Z,
+            sprite,
+            transform.interpolate(alpha) - camera,
+            params,
+        );
+    }
+
+    for (_, (transform, pet)) in world.query::<(&Transform, &Pet)>().iter() {
+        pet.submit(
+            queue,
+            map,
+            transform.interpolate(alpha),
+            transform.flip,
+            camera,
+        );
+    }
+
+    for (_, (transform, player, attachment)) in world
+        .query::<(&Transform, &Player, &FootholdAttachment)>()
+        .iter()
+    {
+        let flip = transform.flip ^ player.avatar.flip();
+        let layer = map.layer_of(attachment.foothold);
+        let position = transform.interpolate(alpha) - camera;
+        for sprite in &player.avatar.frame() {
+            queue.sprite(layer, DrawQueue::LIFE_Z, sprite, position, flip);
+        }
+    }
+}
+
+/// Draws the pets' and players' name tags and chat balloons, over the whole map.
+pub fn draw_overhead(
+    world: &World,
+    renderer: &mut Renderer,
+    name_tag: &NameTag,
+    balloon_style: &ChatBalloonStyle,
+    camera: Vec2,
+    alpha: f32,
+) {
+    for (_, (transform, pet)) in world.query::<(&Transform, &Pet)>().iter() {
+        let position = transform.interpolate(alpha) - camera;
+        pet.draw_overhead(renderer, name_tag, balloon_style, position);
+    }
+    for (_, (transform, player)) in world.query::<(&Transform, &Player)>().iter() {
+        let position = transform.interpolate(alpha) - camera;
+        name_tag.draw(renderer, &player.name, position);
+        if let Some(balloon) = &player.balloon {
+            let top = player
+                .avatar
+                .frame()
+                .iter()
+                .map(|sprite| -sprite.origin.y)
+                .fold(0.0, f32::min);
+            balloon.draw(balloon_style, renderer, position + vec2(0.0, top));
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use glam::{vec2, Vec2};
+use sdl_sys::SDL_FRect;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::sdl::Renderer;
+
+/// Runs the simulation in fixed steps whatever the frame rate, leaving
+/// rendering to interpolate between the last two steps.
+pub struct GameLoop {
+    /// ms per simulation step.
+    pub step: f32,
+    /// Frames per second to stay under, on top of vsync.
+    pub fps_cap: Option<u32>,
+    accumulator: f32,
+    last: Instant,
+    delta: f32,
+}
+
+impl GameLoop {
+    // longer frames are cut short so a stall doesn't run hundreds of steps
+    const MAX_DELTA: f32 = 250.0;
+
+    /// A loop simulating `rate` steps per second.
+    pub fn new(rate: u32) -> Self {
+        Self {
+            step: 1000.0 / rate as f32,
+            fps_cap: None,
+            accumulator: 0.0,
+            last: Instant::now(),
+            delta: 0.0,
+        }
+    }
+
+    /// Starts a frame, returning how many steps the simulation has to run to
+    /// catch up.
+    pub fn begin(&mut self) -> u32 {
+        let now = Instant::now();
+        self.delta = (now - self.last).as_secs_f32() * 1000.0;
+        self.last = now;
+        self.accumulator += self.delta.min(Self::MAX_DELTA);
+        let steps = (self.accumulator / self.step) as u32;
+        self.accumulator -= steps as f32 * self.step;
+        steps
+    }
+
+    /// ms since the previous frame began.
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    /// How far the frame is past the last step, from 0 to 1 step.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+
+    /// Sleeps out the rest of the frame when over `fps_cap`.
+    pub fn pace(&self) {
+        let Some(cap) = self.fps_cap.filter(|cap| *cap > 0) else {
+            return;
+        };
+        let frame = Duration::from_secs_f32(1.0 / cap as f32);
+        if let Some(rest) = frame.checked_sub(self.last.elapsed()) {
+            std::thread::sleep(rest);
+        }
+    }
+}
+
+/// Times of the last frames as bars, with marks at 60 and 30 FPS.
+#[derive(Default)]
+pub struct FrameGraph {
+    samples: VecDeque<f32>,
+}
+
+impl FrameGraph {
+    const SAMPLES: usize = 120;
+    // ms, one pixel each
+    const HEIGHT: f32 = 50.0;
+
+    pub fn push(&mut self, frame_time: f32) {
+        if self.samples.len() == Self::SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(frame_time);
+    }
+
+    pub fn average(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer, position: Vec2) {
+        renderer.fill_rect(
+            &SDL_FRect {
+                x: position.x,
+                y: position.y,
+                w: Self::SAMPLES as f32,
+                h: Self::HEIGHT,
+            },
+            [0, 0, 0, 160],
+        );
+        for (index, sample) in self.samples.iter().enumerate() {
+            let height = sample.min(Self::HEIGHT);
+            let color = if *sample <= 1000.0 / 60.0 + 1.0 {
+                [0, 220, 0, 255]
+            } else if *sample <= 1000.0 / 30.0 + 1.0 {
+                [230, 200, 0, 255]
+            } else {
+                [230, 0, 0, 255]
+            };
+            renderer.fill_rect(
+                &SDL_FRect {
+                    x: position.x + index as f32,
+                    y: position.y + Self::HEIGHT - height,
+                    w: 1.0,
+                    h: height,
+                },
+                color,
+            );
+        }
+        for fps in [60.0, 30.0] {
+            let y = position.y + Self::HEIGHT - 1000.0 / fps;
+            renderer.render_line(
+                vec2(position.x, y),
+                vec2(position.x + Self::SAMPLES as f32, y),
+                [255, 255, 255, 96],
+            );
+        }
+        let average = self.average();
+        let fps = if average > 0.0 { 1000.0 / average } else { 0.0 };
+        renderer.draw_text(
+            &format!("{average:.1} ms {fps:.0} fps"),
+            position + vec2(0.0, Self::HEIGHT + 2.0),
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use ab_glyph::{point, Font, Glyph, Point, PxScale, ScaleFont};
+use ab_glyph::{point, Font, Glyph, OutlinedGlyph, Point, PxScale, Rect, ScaleFont};
+use glam::{vec2, Vec2};
 use image::{DynamicImage, Rgba, RgbaImage};
 
 /// Simple paragraph layout for glyphs into `target`.
@@ -45,17 +46,53 @@ pub fn layout_paragraph<F, SF>(
     }
 }
 
+/// The caret's x before each of `text`'s characters and after the last, on a
+/// single line.
+pub fn caret_offsets<F: Font>(font: F, font_size: f32, text: &str) -> Vec<f32> {
+    let font = font.as_scaled(PxScale::from(font_size));
+    let mut caret = 0.0;
+    let mut previous = None;
+    let mut offsets = vec![caret];
+    for c in text.chars() {
+        if !c.is_control() {
+            let id = font.glyph_id(c);
+            if let Some(previous) = previous {
+                caret += font.kern(previous, id);
+            }
+            caret += font.h_advance(id);
+            previous = Some(id);
+        }
+        offsets.push(caret);
+    }
+    offsets
+}
+
 pub fn draw_image<F: Font>(font: F, font_size: f32, text: &str) -> RgbaImage {
-    /// Dark red colour
-    const COLOUR: (u8, u8, u8) = (0, 0, 0);
+    draw_paragraph(font, font_size, 10000.0, Rgba([0, 0, 0, 255]), text)
+}
 
+/// The size [`draw_paragraph`] would draw `text` at, without rasterizing it.
+pub fn measure_paragraph<F: Font>(font: F, font_size: f32, max_width: f32, text: &str) -> Vec2 {
+    match outline_paragraph(&font, font_size, max_width, text).1 {
+        Some(bounds) => vec2(bounds.width(), bounds.height()).floor(),
+        None => Vec2::ONE,
+    }
+}
+
+// the glyphs with outlines and the bounds they cover together
+fn outline_paragraph<F: Font>(
+    font: &F,
+    font_size: f32,
+    max_width: f32,
+    text: &str,
+) -> (Vec<OutlinedGlyph>, Option<Rect>) {
     // The font size to use
     let scale = PxScale::from(font_size);
 
     let scaled_font = font.as_scaled(scale);
 
     let mut glyphs = Vec::new();
-    layout_paragraph(scaled_font, point(0.0, 0.0), 10000.0, text, &mut glyphs);
+    layout_paragraph(scaled_font, point(0.0, 0.0), max_width, text, &mut glyphs);
 
     // to work out the exact size needed for the drawn glyphs we need to outline
     // them and use their `px_bounds` which hold the coords of their render bounds.
@@ -66,7 +103,7 @@ pub fn draw_image<F: Font>(font: F, font_size: f32, text: &str) -> RgbaImage {
         .collect();
 
     // combine px_bounds to get min bounding coords for the entire layout
-    let Some(all_px_bounds) = outlined
+    let bounds = outlined
         .iter()
         .map(|g| g.px_bounds())
         .reduce(|mut b, next| {
@@ -75,9 +112,21 @@ pub fn draw_image<F: Font>(font: F, font_size: f32, text: &str) -> RgbaImage {
             b.min.y = b.min.y.min(next.min.y);
             b.max.y = b.max.y.max(next.max.y);
             b
-        })
-    else {
-        panic!("No outlined glyphs?");
+        });
+    (outlined, bounds)
+}
+
+pub fn draw_paragraph<F: Font>(
+    font: F,
+    font_size: f32,
+    max_width: f32,
+    colour: Rgba<u8>,
+    text: &str,
+) -> RgbaImage {
+    let (outlined, bounds) = outline_paragraph(&font, font_size, max_width, text);
+    let Some(all_px_bounds) = bounds else {
+        // whitespace only, nothing to draw
+        return RgbaImage::new(1, 1);
     };
 
     // create a new rgba image using the combined px bound width and height
@@ -99,10 +148,10 @@ pub fn draw_image<F: Font>(font: F, font_size: f32, text: &str) -> RgbaImage {
             let px = image.get_pixel_mut(img_left + x, img_top + y);
             // Turn the coverage into an alpha value (blended with any previous)
             *px = Rgba([
-                COLOUR.0,
-                COLOUR.1,
-                COLOUR.2,
-                px.0[3].saturating_add((v * 255.0) as u8),
+                colour.0[0],
+                colour.0[1],
+                colour.0[2],
+                px.0[3].saturating_add((v * colour.0[3] as f32) as u8),
             ]);
         });
     }
@@ -1 +1,21 @@
+pub mod atlas;
+pub mod backend;
+pub mod character;
+pub mod display;
+pub mod draw_queue;
+pub mod ecs;
+pub mod game_loop;
+pub mod layout;
+pub mod map;
+pub mod mob;
+pub mod mount;
+pub mod npc;
+pub mod overhead;
+pub mod pet;
+pub mod random;
+pub mod scene;
+pub mod sdl;
+pub mod sprite;
+pub mod timer;
+pub mod ui;
 pub mod wz;
@@ -1,29 +1,12 @@
-use character::{Character, ZMap};
-use glam::{vec2, Vec2, Vec2Swizzles};
-use image::DynamicImage;
-use sdl::{NineGridTexture, Renderer, Surface, Texture};
-use sdl_sys::{
-    self, SDL_CreateRenderer, SDL_CreateWindow, SDL_Delay, SDL_Event, SDL_EventType, SDL_FRect,
-    SDL_GetKeyboardState, SDL_GetTicks, SDL_PollEvent, SDL_RenderClear, SDL_RenderPresent,
-    SDL_Scancode::{
-        self, SDL_SCANCODE_DOWN, SDL_SCANCODE_LEFT, SDL_SCANCODE_RIGHT, SDL_SCANCODE_UP,
-    },
-    SDL_SetRenderDrawColor, SDL_SetRenderVSync,
-};
-use sprite::Sprite;
-use ui::Button;
+use client::display::{DisplaySettings, Resolution, Scaling, WindowMode};
+use client::game_loop::{FrameGraph, GameLoop};
+use client::scene::{Context, LoginScene, SceneStack};
+use client::sdl::{self, Renderer};
+use client::{backend, wz};
+use glam::{vec2, Vec2};
+use sdl_sys::{Event, EventPump, SDL_Rect, SDL_Scancode};
 
-use std::{error::Error, mem::MaybeUninit, sync::Arc};
-
-mod character;
-mod layout;
-mod map;
-mod npc;
-mod sdl;
-mod sprite;
-mod timer;
-mod ui;
-mod wz;
+use std::error::Error;
 
 pub fn intersect(p1: &Vec2, p2: &Vec2, p3: &Vec2, p4: &Vec2) -> Option<Vec2> {
     if (f32::max(p1.x, p2.x)) < f32::min(p3.x, p4.x)
@@ -63,574 +46,217 @@ pub fn intersect(p1: &Vec2, p2: &Vec2, p3: &Vec2, p4: &Vec2) -> Option<Vec2> {
     Some(Vec2::new(x, y))
 }
 
-struct PollEvent {
-    event: MaybeUninit<SDL_Event>,
+struct World<'r> {
+    canvas: &'r sdl_sys::Renderer<'r>,
+    sprite_renderer: Renderer<'r>,
+    display: DisplaySettings,
+    size: Vec2,
+    dpr: f32,
+    game_loop: GameLoop,
 }
 
-unsafe impl Send for PollEvent {}
-unsafe impl Sync for PollEvent {}
-
-impl PollEvent {
-    fn new() -> Self {
-        Self {
-            event: MaybeUninit::uninit(),
-        }
+impl<'r> World<'r> {
+    pub fn new(
+        window: &sdl_sys::Window,
+        canvas: &'r sdl_sys::Renderer<'r>,
+        display: DisplaySettings,
+    ) -> Result<Self, sdl_sys::Error> {
+        let sprite_renderer = sdl::Renderer::new(1.0, Box::new(backend::SdlBackend::new(canvas)));
+
+        let mut world = Self {
+            canvas,
+            display,
+            size: display.resolution.size(),
+            dpr: 1.0,
+            // speeds are per step, tuned at 60 FPS
+            game_loop: GameLoop::new(60),
+            sprite_renderer,
+        };
+        world.set_display(window, display)?;
+        Ok(world)
     }
-}
 
-impl Iterator for &mut PollEvent {
-    type Item = SDL_Event;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        unsafe {
-            if SDL_PollEvent(self.event.as_mut_ptr()) == 1 {
-                Some(self.event.assume_init())
-            } else {
-                None
-            }
-        }
+    pub fn set_display(
+        &mut self,
+        window: &sdl_sys::Window,
+        display: DisplaySettings,
+    ) -> Result<(), sdl_sys::Error> {
+        self.display = display;
+        display.apply(window, self.canvas)?;
+        self.fit(window)
     }
-}
-
-#[derive(Default)]
-struct Camera {
-    position: Vec2,
-    direction: Vec2,
-    speed: Vec2,
-}
 
-#[derive(Default)]
-struct Player {
-    avatar: Character,
-    position: Vec2,
-    direction: Vec2,
-    speed: Vec2,
-    flip: bool,
-    foothold: i32,
-}
-
-struct World {
-    window: *mut sdl_sys::SDL_Window,
-    renderer: *mut sdl_sys::SDL_Renderer,
-    sprite_renderer: Renderer,
-    size: Vec2,
-    dpr: f32,
-    ticks: u64,
-    delta: f32,
-    camera: Camera,
+    /// Follows the window's size and pixel density, which change on resizes
+    /// and when it moves to another display.
+    pub fn fit(&mut self, window: &sdl_sys::Window) -> Result<(), sdl_sys::Error> {
+        let (size, dpr) = self.display.fit(window, self.canvas)?;
+        self.size = size;
+        self.dpr = dpr;
+        self.sprite_renderer.set_dpr(dpr);
+        Ok(())
+    }
 }
 
-impl World {
-    pub fn new() -> Self {
-        unsafe {
-            sdl_sys::SDL_Init(sdl_sys::SDL_INIT_VIDEO);
+/// Pointer events with their positions in logical units.
+fn logical(canvas: &sdl_sys::Renderer, event: Event) -> Result<Event, sdl_sys::Error> {
+    Ok(match event {
+        Event::MouseMotion { x, y } => {
+            let (x, y) = canvas.coordinates_from_window(x, y)?;
+            Event::MouseMotion { x, y }
         }
-
-        // let size = vec2(1024.0, 768.0);
-        let size = vec2(800.0, 600.0);
-        let window =
-            unsafe { SDL_CreateWindow(c"Maple RS".as_ptr(), size.x as i32, size.y as i32, 0x2000) };
-        let renderer = unsafe { SDL_CreateRenderer(window, std::ptr::null()) };
-
-        let dpr = unsafe { sdl_sys::SDL_GetWindowPixelDensity(window) };
-
-        unsafe {
-            sdl_sys::SDL_SetRenderScale(renderer, dpr, dpr);
+        Event::MouseButtonDown { button, x, y } => {
+            let (x, y) = canvas.coordinates_from_window(x, y)?;
+            Event::MouseButtonDown { button, x, y }
         }
-
-        let sprite_renderer = sdl::Renderer::new(dpr, renderer);
-
-        let ticks = unsafe { SDL_GetTicks() };
-
-        Self {
-            size,
-            window,
-            renderer,
-            dpr,
-            ticks,
-            delta: 0.0,
-            sprite_renderer,
-            camera: Camera {
-                speed: Vec2::ONE * 40.0,
-                ..Default::default()
-            },
+        Event::MouseButtonUp { button, x, y } => {
+            let (x, y) = canvas.coordinates_from_window(x, y)?;
+            Event::MouseButtonUp { button, x, y }
         }
-    }
-
-    pub fn tick(&mut self) {
-        let now = unsafe { SDL_GetTicks() };
-        self.delta = (now - self.ticks) as f32;
-        self.ticks = now;
-    }
+        event => event,
+    })
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let node = wz::resolve_base().unwrap();
-    let mut map = map::Map::new(&node, "002000000").unwrap();
-    // let mut map = map::Map::new(&node, "222020111").unwrap();
-    let position = map.portals.iter().fold(None, |acc: Option<Vec2>, item| {
-        if item.pn != "sp" {
-            return acc;
-        }
-        if let Some(prev) = acc {
-            if item.position.length() > prev.length() {
-                Some(item.position)
-            } else {
-                acc
-            }
-        } else {
-            Some(item.position)
-        }
-    });
-    let z_map: Arc<ZMap> = Arc::new(node.at_path("zmap.img").unwrap().into());
-    let mut player = Player {
-        avatar: character::Character::new(
-            [
-                "00002000",
-                "00012000",
-                "Hair/00030000",
-                "Coat/01040036",
-                "Pants/01060026",
-                "Shoes/01071000",
-                "Face/00020000",
-            ]
-            .iter()
-            .map(|path| node.at_path(&format!("Character/{path}.img")).unwrap())
-            .collect(),
-            z_map,
-        ),
-        position: position.unwrap_or_default(),
-        direction: Vec2::ZERO,
-        speed: Vec2::ONE * 40.0,
-        ..Default::default()
-    };
-
-    let world_map_node = node.at_path("UI/UIWindow.img/WorldMap").unwrap();
-    let world_map_title: sprite::Sprite = world_map_node.get("title").into();
-    let world_map_border: Vec<Arc<DynamicImage>> = node
-        .at_path("UI/UIWindow.img/WorldMap/Border")
-        .unwrap()
-        .into();
-
-    let world_map_helper_images: Vec<Sprite> = node
-        .at_path("Map/MapHelper.img/worldMap/mapImage")
-        .unwrap()
-        .into();
-    let world_map =
-        map::world_map::WorldMap::from(node.at_path("Map/WorldMap/WorldMap.img").unwrap());
-
-    let mut world = World::new();
-    let mut events = PollEvent::new();
-    let state = unsafe { SDL_GetKeyboardState(std::ptr::null_mut() as *mut core::ffi::c_int) };
-
-    let surfaces = world_map_border
-        .into_iter()
-        .map(|item| item.into())
-        .collect::<Vec<Surface>>();
-
-    let tex = sdl::NineGridTexture::new(
-        (
-            &surfaces[0],
-            &surfaces[1],
-            &surfaces[2],
-            &surfaces[3],
-            &surfaces[4],
-            &surfaces[5],
-            &surfaces[6],
-            &surfaces[7],
-        ),
-        world.renderer,
-    );
-
-    let btn_close = ui::Button::from(node.at_path("UI/Basic.img/BtClose").unwrap());
-
-    let tooltip_bg: DynamicImage = image::load_from_memory(include_bytes!("./tooltip.png"))
-        .unwrap()
-        .into();
-
-    let tooltip_tex = NineGridTexture {
-        texture: Texture::from_image(&tooltip_bg, world.renderer),
-        left_width: 4,
-        middle_width: 0,
-        right_width: 4,
-        top_height: 4,
-        middle_height: 0,
-        bottom_height: 4,
-    };
-    unsafe {
-        SDL_SetRenderVSync(world.renderer, 1);
 
-        let mut exited = false;
-
-        while !exited {
-            {
-                world.tick();
-
-                let camera = &mut world.camera;
-
-                let pressed_left = *state.offset(SDL_SCANCODE_LEFT as isize) != 0;
-                let pressed_right = *state.offset(SDL_SCANCODE_RIGHT as isize) != 0;
-                let pressed_up = *state.offset(SDL_SCANCODE_UP as isize) != 0;
-                let pressed_down = *state.offset(SDL_SCANCODE_DOWN as isize) != 0;
-
-                let prev = player.direction;
-                for event in &mut events {
-                    match event.type_ as SDL_EventType::Type {
-                        SDL_EventType::SDL_EVENT_QUIT => {
-                            exited = true;
-                        }
-                        SDL_EventType::SDL_EVENT_KEY_DOWN => match event.key.scancode {
-                            SDL_Scancode::SDL_SCANCODE_LEFT => {
-                                player.direction.x = -1.0;
-                            }
-                            SDL_Scancode::SDL_SCANCODE_RIGHT => {
-                                player.direction.x = 1.0;
-                            }
-                            SDL_Scancode::SDL_SCANCODE_UP => {
-                                player.direction.y = -1.0;
-                            }
-                            SDL_Scancode::SDL_SCANCODE_DOWN => {
-                                player.direction.y = 1.0;
-                            }
-                            _ => {}
-                        },
-                        SDL_EventType::SDL_EVENT_KEY_UP => match event.key.scancode {
-                            SDL_Scancode::SDL_SCANCODE_LEFT => {
-                                player.direction.x = if pressed_right { 1.0 } else { 0.0 };
-                            }
-                            SDL_Scancode::SDL_SCANCODE_RIGHT => {
-                                player.direction.x = if pressed_left { -1.0 } else { 0.0 };
-                            }
-                            SDL_Scancode::SDL_SCANCODE_UP => {
-                                player.direction.y = if pressed_down { 1.0 } else { 0.0 };
-                            }
-                            SDL_Scancode::SDL_SCANCODE_DOWN => {
-                                player.direction.y = if pressed_up { -1.0 } else { 0.0 };
-                            }
-                            _ => {}
-                        },
-                        _ => {}
+    let sdl = sdl_sys::Sdl::init(sdl_sys::SDL_INIT_VIDEO)?;
+    let display = DisplaySettings::default();
+    let Resolution { width, height } = display.resolution;
+    // SDL_WINDOW_RESIZABLE | SDL_WINDOW_HIGH_PIXEL_DENSITY
+    let window = sdl_sys::Window::new(&sdl, "Maple RS", width as i32, height as i32, 0x2020)?;
+    let canvas = window.create_renderer()?;
+    let mut world = World::new(&window, &canvas, display)?;
+    let mut events = EventPump::new(&sdl);
+    let keys = sdl_sys::keyboard_state();
+    canvas.set_vsync(1)?;
+
+    let mut scenes = SceneStack::new(Box::new(LoginScene::new(&node)));
+    let mut frame_graph = FrameGraph::default();
+    let mut show_frame_graph = false;
+    let mut text_input = None;
+
+    while !scenes.quit() {
+        let steps = world.game_loop.begin();
+        frame_graph.push(world.game_loop.delta());
+
+        let mouse = sdl_sys::mouse_state();
+        let (x, y) = canvas.coordinates_from_window(mouse.x, mouse.y)?;
+        let mut display = world.display;
+        let mut refit = false;
+        {
+            let mut ctx = Context {
+                root: &node,
+                renderer: &mut world.sprite_renderer,
+                size: world.size,
+                mouse: vec2(x, y),
+                keys,
+                text_input: None,
+            };
+
+            for event in &mut events {
+                match event {
+                    Event::Quit => return Ok(()),
+                    Event::WindowResized { .. }
+                    | Event::WindowPixelSizeChanged { .. }
+                    | Event::WindowDisplayScaleChanged => {
+                        refit = true;
                     }
-                }
-
-                if player.direction.x > 0.0 && !player.flip
-                    || player.direction.x < 0.0 && player.flip
-                {
-                    player.flip = !player.flip;
-                }
-
-                if prev.x == 0.0 && player.direction.x != 0.0 {
-                    player.avatar.set_action("walk1");
-                }
-
-                if prev.x != 0.0 && player.direction.x == 0.0 {
-                    player.avatar.set_action("stand1");
-                }
-
-                let direction = player.direction;
-                let speed = player.speed;
-                player.position += direction * speed;
-                camera.position = player.position - world.size / 2.0;
-                // camera.position = player.position;
-            }
-
-            let mut mouse_x = MaybeUninit::<f32>::uninit();
-            let mut mouse_y = MaybeUninit::<f32>::uninit();
-            sdl_sys::SDL_GetMouseState(mouse_x.as_mut_ptr(), mouse_y.as_mut_ptr());
-
-            let mouse = { vec2(mouse_x.assume_init() as f32, mouse_y.assume_init() as f32) };
-
-            SDL_SetRenderDrawColor(world.renderer, 0, 0, 0, 255);
-            SDL_RenderClear(world.renderer);
-
-            fn draw_back(world: &mut World, item: &mut map::MapBackground) {
-                let World {
-                    delta,
-                    size,
-                    sprite_renderer,
-                    ..
-                } = world;
-                let delta = *delta;
-
-                let offset = world.camera.position + *size / 2.0;
-                match item.r#type {
-                    4 | 6 => {
-                        item.offset_x += item.rx as f32 * 5.0 * delta / 1000.0;
-                        item.offset_y = item.y + offset.y * (item.ry + 100) as f32 / 100.0;
+                    Event::KeyDown {
+                        scancode: SDL_Scancode::SDL_SCANCODE_F7,
+                        ..
+                    } => {
+                        show_frame_graph = !show_frame_graph;
                     }
-                    5 | 7 => {
-                        item.offset_x = item.x + offset.x * (item.rx + 100) as f32 / 100.0;
-                        item.offset_y += item.ry as f32 * 5.0 * delta / 1000.0;
+                    Event::KeyDown {
+                        scancode: SDL_Scancode::SDL_SCANCODE_F8,
+                        ..
+                    } => {
+                        world.game_loop.fps_cap = match world.game_loop.fps_cap {
+                            None => Some(30),
+                            Some(30) => Some(60),
+                            Some(60) => Some(144),
+                            Some(_) => None,
+                        };
                     }
-                    _ => {
-                        item.offset_x = item.x + offset.x * (item.rx + 100) as f32 / 100.0;
-                        item.offset_y = item.y + offset.y * (item.ry + 100) as f32 / 100.0;
+                    Event::KeyDown {
+                        scancode: SDL_Scancode::SDL_SCANCODE_F9,
+                        ..
+                    } => {
+                        display.scaling = match display.scaling {
+                            Scaling::Letterbox => Scaling::Integer,
+                            Scaling::Integer => Scaling::Stretch,
+                            Scaling::Stretch => Scaling::Native,
+                            Scaling::Native => Scaling::Letterbox,
+                        };
                     }
-                }
-
-                let sprite = match &mut item.sprite {
-                    map::Drawable::Sprite(sprite) => sprite,
-                    map::Drawable::SpriteAnimation(animation) => animation.tick(delta),
-                };
-                let w = sprite.image.width() as f32;
-                let h = sprite.image.height() as f32;
-                let cw = if item.cx > 0 { item.cx as f32 } else { w };
-                let ch = if item.cy > 0 { item.cy as f32 } else { h };
-
-                let x = item.offset_x;
-                let y = item.offset_y;
-                let lb = x - sprite.origin.x;
-                let rb = lb + w;
-                let tb = y - sprite.origin.y;
-                let bb = tb + h;
-
-                let hs = f32::ceil((world.camera.position.x - rb) / cw) as i32;
-                let he = f32::ceil((world.camera.position.x + size.x - rb) / cw) as i32 + 1;
-
-                let vs = f32::ceil((world.camera.position.y - bb) / ch) as i32;
-                let ve = f32::ceil((world.camera.position.y + size.y - bb) / ch) as i32 + 1;
-
-                match item.r#type {
-                    1 | 4 => {
-                        for i in hs..he {
-                            sprite_renderer.draw_flip(
-                                sprite,
-                                vec2(x + i as f32 * cw, y) - world.camera.position,
-                                item.flip,
-                            );
-                        }
-                    }
-                    2 | 5 => {
-                        for i in vs..ve {
-                            sprite_renderer.draw_flip(
-                                sprite,
-                                vec2(x, y + i as f32 * ch) - world.camera.position,
-                                item.flip,
-                            );
-                        }
-                    }
-                    3 | 6 | 7 => {
-                        for i in vs..ve {
-                            for j in hs..he {
-                                sprite_renderer.draw_flip(
-                                    sprite,
-                                    vec2(x + j as f32 * cw, y + i as f32 * ch)
-                                        - world.camera.position,
-                                    item.flip,
-                                );
-                            }
-                        }
-                    }
-                    _ => {
-                        sprite_renderer.draw_flip(
-                            sprite,
-                            vec2(x, y) - world.camera.position,
-                            item.flip,
-                        );
-                    } // _ => {}
-                }
-
-                // unsafe {
-                //     sdl_sys::SDL_RenderRect(
-                //         renderer,
-                //         &SDL_FRect {
-                //             x: x - sprite.origin.x - world.camera.position.x,
-                //             y: y - sprite.origin.y - world.camera.position.y,
-                //             w,
-                //             h,
-                //         },
-                //     );
-                // }
-                // sprite_renderer.draw(
-                //     &sprite.image,
-                //     sprite.origin,
-                //     vec2(x as f32, y as f32) - world.camera.position,
-                // );
-            }
-
-            for item in &mut map.backgrounds {
-                if !item.front {
-                    draw_back(&mut world, item);
-                }
-            }
-
-            let delta = world.delta;
-
-            {
-                let sprite_renderer = &mut world.sprite_renderer;
-
-                for layer in &mut map.layers {
-                    for item in &mut layer.objects {
-                        item.timer.tick(delta);
-                        let sprite = &item.sprites[item.timer.index];
-                        sprite_renderer.draw_flip(
-                            sprite,
-                            item.position - world.camera.position,
-                            item.flip,
-                        );
+                    Event::KeyDown {
+                        scancode: SDL_Scancode::SDL_SCANCODE_F10,
+                        ..
+                    } => {
+                        display.resolution = display.resolution.next();
                     }
-
-                    for item in &mut layer.tiles {
-                        sprite_renderer.draw(&item.tile, item.position - world.camera.position);
+                    Event::KeyDown {
+                        scancode: SDL_Scancode::SDL_SCANCODE_F11,
+                        ..
+                    } => {
+                        display.mode = match display.mode {
+                            WindowMode::Windowed => WindowMode::Borderless,
+                            WindowMode::Borderless => WindowMode::Fullscreen,
+                            WindowMode::Fullscreen => WindowMode::Windowed,
+                        };
                     }
-                }
-            }
-
-            map.portal_timer.tick(delta);
-            let sprite = &map.helper.pv[map.portal_timer.index];
-            for item in map.portals.iter() {
-                if item.pn == "sp" {
-                    continue;
-                }
-                world
-                    .sprite_renderer
-                    .draw(&sprite, item.position - world.camera.position);
-            }
-
-            {
-                let sprite_renderer = &mut world.sprite_renderer;
-                for item in &mut map.life {
-                    if item.r#type == "n" {
-                        let npc = map.npc.get_mut(&item.id).unwrap();
-                        let action = npc.actions.get_mut("stand").unwrap();
-                        action.timer.tick(delta);
-                        let sprite = &action.frames[action.timer.index];
-                        sprite_renderer.draw_flip(
-                            sprite,
-                            vec2(item.x as f32, item.cy as f32) - world.camera.position,
-                            item.f == 1,
-                        );
+                    event => {
+                        let event = logical(&canvas, event)?;
+                        scenes.event(&mut ctx, &event);
                     }
                 }
             }
 
-            {
-                let sprite_renderer = &mut world.sprite_renderer;
-                player.avatar.tick(delta);
-                for sprite in player.avatar.frame() {
-                    sprite_renderer.draw_flip(
-                        &sprite,
-                        player.position - world.camera.position,
-                        player.flip,
-                    )
-                }
+            let delta = world.game_loop.delta();
+            scenes.update(&mut ctx, delta);
+            for _ in 0..steps {
+                scenes.step(&mut ctx, world.game_loop.step);
             }
 
-            for item in &mut map.backgrounds {
-                if item.front {
-                    draw_back(&mut world, item);
-                }
+            ctx.renderer.clear([0, 0, 0, 255]);
+            scenes.draw(&mut ctx, delta, world.game_loop.alpha());
+            if show_frame_graph {
+                frame_graph.draw(ctx.renderer, vec2(8.0, 8.0));
             }
-
-            // {
-            //     let sprite_renderer = &mut world.sprite_renderer;
-            //     for layer in &mut map.layers {
-            //         for item in &mut layer.objects {
-            //             for sprite in &item.sprites {
-            //                 sprite_renderer.draw_text(
-            //                     &format!("{}", sprite.path),
-            //                     item.position - world.camera.position,
-            //                 );
-            //             }
-            //         }
-            //     }
-            // }
-
-            // let textures = {
-            //     let sprite_renderer = &mut world.sprite_renderer;
-            //     let mut textures = vec![];
-            //     for item in &world_map_border {
-            //         textures.push(sprite_renderer.texture(item));
-            //     }
-            //     textures
-            // };
-
-            let content_size = vec2(640.0, 470.0);
-            let window_size = content_size + tex.border_size();
-            let window_offset = (world.size - window_size) / 2.0;
-            tex.draw(window_offset, window_size);
-
-            let frame = &btn_close.normal.frames[0];
-            world.sprite_renderer.draw(
-                &world_map_title,
-                window_offset + vec2(tex.left_width as f32 + 4.0, 9.5),
-            );
-            world.sprite_renderer.draw(
-                frame,
-                window_offset
-                    + vec2(window_size.x, 0.0)
-                    + vec2(-(tex.right_width as f32 + frame.image.width() as f32), 6.0),
-            );
-
-            let content_position = window_offset
-                + vec2(tex.left_width as f32, tex.top_height as f32)
-                + (content_size / 2.0);
-            world
-                .sprite_renderer
-                .draw(&world_map.base_img, content_position);
-
-            for (_, item) in world_map.map_link.iter() {
-                let lt = content_position - item.link_img.origin;
-                let rb = lt + item.link_img.size;
-                if (mouse.cmpge(lt).all()) && mouse.cmplt(rb).all() {
-                    let pt = mouse - lt;
-
-                    let pixel = item
-                        .link_img
-                        .image
-                        .as_rgba8()
-                        .unwrap()
-                        .get_pixel(pt.x as u32, pt.y as u32);
-
-                    if pixel.0[3] > 0 {
-                        world.sprite_renderer.draw(&item.link_img, content_position);
-                        break;
+            ctx.renderer.present();
+
+            if ctx.text_input != text_input {
+                text_input = ctx.text_input;
+                match text_input {
+                    Some((rect, caret)) => {
+                        let (x, y) =
+                            canvas.coordinates_to_window(rect.position.x, rect.position.y)?;
+                        let end = rect.position + rect.size;
+                        let (right, bottom) = canvas.coordinates_to_window(end.x, end.y)?;
+                        let (caret, _) = canvas
+                            .coordinates_to_window(rect.position.x + caret, rect.position.y)?;
+                        let area = SDL_Rect {
+                            x: x as i32,
+                            y: y as i32,
+                            w: (right - x) as i32,
+                            h: (bottom - y) as i32,
+                        };
+                        window.set_text_input_area(&area, (caret - x) as i32)?;
+                        if !window.text_input_active() {
+                            window.start_text_input()?;
+                        }
                     }
+                    None => window.stop_text_input()?,
                 }
-
-                // let size = vec2(
-                //     item.link_img.image.width() as f32,
-                //     item.link_img.image.height() as f32,
-                // );
-                // world.sprite_renderer.render_rect(&SDL_FRect {
-                //     x: p.x,
-                //     y: p.y,
-                //     w: size.x,
-                //     h: size.y,
-                // })
-            }
-
-            for (_, item) in world_map.map_list.iter() {
-                // if let Some(path) = &item.path {
-                //     world.sprite_renderer.draw(path, content_position);
-                // }
-                let spot_image = &world_map_helper_images[3];
-                world
-                    .sprite_renderer
-                    .draw(spot_image, content_position + item.spot);
-                // world.sprite_renderer.render_rect(&SDL_FRect {
-                //     x: item.spot.x + content_position.x,
-                //     y: item.spot.y + content_position.y,
-                //     w: 20.0,
-                //     h: 20.0,
-                // })
             }
+        }
 
-            tooltip_tex.draw(vec2(50.0, 50.0), vec2(400.0, 400.0));
-            // sdl_sys::SDL_RenderTexture(
-            //     world.renderer,
-            //     tex.texture,
-            //     std::ptr::null(),
-            //     &SDL_FRect {
-            //         x: 0.0,
-            //         y: 0.0,
-            //         w: tex.size.x,
-            //         h: tex.size.y,
-            //     },
-            // );
-            SDL_RenderPresent(world.renderer);
-            SDL_Delay(16);
+        if display != world.display {
+            world.set_display(&window, display)?;
+        } else if refit {
+            world.fit(&window)?;
         }
+        world.game_loop.pace();
     }
 
     Ok(())
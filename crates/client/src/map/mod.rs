@@ -1,14 +1,21 @@
 use glam::{vec2, Vec2};
 use std::collections::HashMap;
+use std::f32::consts::TAU;
 use wz_reader::node::Error;
 
+use crate::atlas::Atlas;
+use crate::mob::Mob;
 use crate::npc::Npc;
-use crate::sprite::{self, Sprite, SpriteAnimation};
+use crate::sprite::{self, animation_property, Sprite, SpriteAnimation};
 use crate::timer::Timer;
 use crate::wz::Node;
 
+pub mod names;
+mod render;
 pub mod world_map;
 
+pub use render::MapOverlay;
+
 pub struct MapHelper {
     pub pv: Vec<Sprite>,
 }
@@ -57,24 +64,92 @@ pub struct Foothold {
     pub z_mass: i32,
 }
 
+impl Foothold {
+    /// Height of the foothold at `x`, or `None` for walls, undersides and
+    /// footholds that don't span `x`.
+    pub fn y_at(&self, x: f32) -> Option<f32> {
+        if self.start.x >= self.end.x || x < self.start.x || x > self.end.x {
+            return None;
+        }
+        let t = (x - self.start.x) / (self.end.x - self.start.x);
+        Some(self.start.y + (self.end.y - self.start.y) * t)
+    }
+}
+
+pub struct LadderRope {
+    // ladder when set, rope otherwise
+    pub l: bool,
+    pub uf: bool,
+    pub x: i32,
+    pub y1: i32,
+    pub y2: i32,
+    pub page: i32,
+}
+
+impl From<Node> for LadderRope {
+    fn from(node: Node) -> Self {
+        Self {
+            l: node.get("l").into(),
+            uf: node.get("uf").into(),
+            x: node.get("x").into(),
+            y1: node.get("y1").into(),
+            y2: node.get("y2").into(),
+            page: node.get("page").into(),
+        }
+    }
+}
+
 pub struct MapInfo {
-    vr_top: i32,
-    vr_bottom: i32,
-    vr_left: i32,
-    vr_right: i32,
+    pub vr_top: i32,
+    pub vr_bottom: i32,
+    pub vr_left: i32,
+    pub vr_right: i32,
 }
 
 impl From<Node> for MapInfo {
     fn from(node: Node) -> Self {
         Self {
-            vr_top: node.get("VRTop").into(),
-            vr_bottom: node.get("VRBottom").into(),
-            vr_left: node.get("VRLeft").into(),
-            vr_right: node.get("VRRight").into(),
+            vr_top: node.try_get("VRTop").map(Into::into).unwrap_or(0),
+            vr_bottom: node.try_get("VRBottom").map(Into::into).unwrap_or(0),
+            vr_left: node.try_get("VRLeft").map(Into::into).unwrap_or(0),
+            vr_right: node.try_get("VRRight").map(Into::into).unwrap_or(0),
+        }
+    }
+}
+
+/// The map's `miniMap`, a scaled down picture of it.
+#[derive(Clone)]
+pub struct Minimap {
+    pub canvas: Sprite,
+    /// Added to world positions before scaling.
+    pub center: Vec2,
+    /// World positions are scaled down by 2^mag.
+    pub mag: i32,
+    pub size: Vec2,
+}
+
+impl From<Node> for Minimap {
+    fn from(node: Node) -> Self {
+        let x: i32 = node.get("centerX").into();
+        let y: i32 = node.get("centerY").into();
+        let width: i32 = node.get("width").into();
+        let height: i32 = node.get("height").into();
+        Self {
+            canvas: node.get("canvas").into(),
+            center: vec2(x as f32, y as f32),
+            mag: node.try_get("mag").map(Into::into).unwrap_or(0),
+            size: vec2(width as f32, height as f32),
         }
     }
 }
 
+impl Minimap {
+    /// Where `position` in the world is on the canvas.
+    pub fn to_minimap(&self, position: Vec2) -> Vec2 {
+        (position + self.center) / (1 << self.mag) as f32
+    }
+}
+
 pub struct MapObject {
     id: i32,
     pub timer: Timer,
@@ -82,6 +157,66 @@ pub struct MapObject {
     pub sprites: Vec<Sprite>,
     pub position: Vec2,
     pub z: i32,
+    pub motion: ObjectMotion,
+    // ms since the map was loaded, drives `motion`
+    pub elapsed: f32,
+}
+
+/// Movement of a map object, from the `moveType`, `moveW`, `moveH`, `moveP`,
+/// `moveR` and `rotateSpeed` properties of its animation.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ObjectMotion {
+    // 0 none, 1 horizontal, 2 vertical, 3 both
+    pub r#type: i32,
+    pub w: f32,
+    pub h: f32,
+    // ms per swing
+    pub period: f32,
+    // ms per turn
+    pub rotation_period: f32,
+    // degrees per second
+    pub rotate_speed: f32,
+}
+
+impl ObjectMotion {
+    pub fn new(node: &Node) -> Self {
+        let get =
+            |name| animation_property(node, name).map_or(0.0, |value| i32::from(value) as f32);
+        Self {
+            r#type: get("moveType") as i32,
+            w: get("moveW"),
+            h: get("moveH"),
+            period: get("moveP"),
+            rotation_period: get("moveR"),
+            rotate_speed: get("rotateSpeed"),
+        }
+    }
+
+    /// Offset from the object's position `time` ms in, swinging along a sine.
+    pub fn offset(&self, time: f32) -> Vec2 {
+        let period = if self.period > 0.0 {
+            self.period
+        } else {
+            TAU * 1000.0
+        };
+        let phase = time / period * TAU;
+        match self.r#type {
+            1 => vec2(self.w * phase.sin(), 0.0),
+            2 => vec2(0.0, self.h * phase.sin()),
+            3 => vec2(self.w * phase.cos(), self.h * phase.sin()),
+            _ => Vec2::ZERO,
+        }
+    }
+
+    /// Clockwise rotation in degrees `time` ms in.
+    pub fn rotation(&self, time: f32) -> f32 {
+        let angle = if self.rotation_period != 0.0 {
+            360.0 * time / self.rotation_period
+        } else {
+            self.rotate_speed * time / 1000.0
+        };
+        angle % 360.0
+    }
 }
 
 pub enum MapItem {
@@ -169,12 +304,14 @@ impl From<Node> for MapLife {
 pub enum Drawable {
     Sprite(sprite::Sprite),
     SpriteAnimation(sprite::SpriteAnimation),
+    /// Spine backgrounds and missing images, not drawn. Keeps the path.
+    Placeholder(String),
 }
 
 impl MapBackground {
     pub fn new(root: Node, node: Node) -> Self {
         let bs: String = node.get("bS").into();
-        let ani: i32 = node.get("ani").into();
+        let ani: i32 = node.try_get("ani").map(Into::into).unwrap_or(0);
         let no: i32 = node.get("no").into();
 
         let path = format!(
@@ -184,21 +321,24 @@ impl MapBackground {
                 0 => "back",
                 1 => "ani",
                 2 => "spine",
-                _ => panic!("unknown ani: {}", ani),
+                _ => "unknown",
             },
             no
         );
 
-        let back_node = root.at_path(&path).unwrap();
+        // there's no spine runtime, those are left out like missing images
+        let sprite = match root.at_path(&path) {
+            Ok(back_node) if ani == 0 => Drawable::Sprite(Sprite::from(back_node)),
+            Ok(back_node) if ani == 1 => {
+                Drawable::SpriteAnimation(SpriteAnimation::from(back_node))
+            }
+            _ => Drawable::Placeholder(path),
+        };
 
         let x = i32::from(node.get("x")) as f32;
         let y = i32::from(node.get("y")) as f32;
         let background = Self {
-            sprite: if ani == 0 {
-                Drawable::Sprite(Sprite::from(back_node))
-            } else {
-                Drawable::SpriteAnimation(SpriteAnimation::from(back_node))
-            },
+            sprite,
             offset_x: x,
             offset_y: y,
             bs,
@@ -213,7 +353,7 @@ impl MapBackground {
             r#type: node.get("type").into(),
             rx: node.get("rx").into(),
             ry: node.get("ry").into(),
-            a: node.get("a").into(),
+            a: node.try_get("a").map(Into::into).unwrap_or(255),
         };
         // 0 无平铺
         // 1 水平平铺
@@ -227,18 +367,122 @@ impl MapBackground {
     }
 }
 pub struct Map {
+    pub id: i32,
     pub npc: HashMap<String, Npc>,
+    pub mob: HashMap<String, Mob>,
     pub life: Vec<MapLife>,
     pub backgrounds: Vec<MapBackground>,
     pub layers: Vec<MapLayer>,
     pub footholds: HashMap<i32, Foothold>,
     pub portals: Vec<Portal>,
+    pub ladders: Vec<LadderRope>,
     pub helper: MapHelper,
     pub portal_timer: Timer,
     pub info: MapInfo,
+    pub minimap: Option<Minimap>,
 }
 
 impl Map {
+    /// The nearest foothold at or below `position`, with its height there.
+    pub fn foothold_below(&self, position: Vec2) -> Option<(i32, f32)> {
+        self.footholds
+            .iter()
+            .filter_map(|(id, foothold)| Some((*id, foothold.y_at(position.x)?)))
+            .filter(|(_, y)| *y >= position.y)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    pub fn top_layer(&self) -> i32 {
+        self.layers.len() as i32 - 1
+    }
+
+    /// The map layer a foothold belongs to, the top one for unknown ids.
+    pub fn layer_of(&self, foothold: i32) -> i32 {
+        self.footholds
+            .get(&foothold)
+            .map_or(self.top_layer(), |item| item.page)
+    }
+
+    /// The layer of whatever stands at `position`, going by the foothold
+    /// under it.
+    pub fn layer_at(&self, position: Vec2) -> i32 {
+        match self.foothold_below(position - vec2(0.0, 1.0)) {
+            Some((id, _)) => self.layer_of(id),
+            None => self.top_layer(),
+        }
+    }
+
+    /// Packs the images of everything the map draws, so that a renderer can
+    /// batch them.
+    pub fn atlas(&self) -> Atlas {
+        let backgrounds = self.backgrounds.iter().flat_map(|item| match &item.sprite {
+            Drawable::Sprite(sprite) => std::slice::from_ref(sprite),
+            Drawable::SpriteAnimation(animation) => animation.frames.as_slice(),
+            Drawable::Placeholder(_) => &[],
+        });
+        let layers = self.layers.iter().flat_map(|layer| {
+            layer
+                .tiles
+                .iter()
+                .map(|item| &item.tile)
+                .chain(layer.objects.iter().flat_map(|item| &item.sprites))
+        });
+        let npcs = self
+            .npc
+            .values()
+            .flat_map(|npc| npc.actions.values())
+            .chain(self.mob.values().flat_map(|mob| mob.actions.values()))
+            .flat_map(|action| &action.frames);
+        Atlas::pack(
+            backgrounds
+                .chain(layers)
+                .chain(&self.helper.pv)
+                .chain(npcs)
+                .map(|sprite| &sprite.image),
+        )
+    }
+
+    /// The spawn point furthest from the origin, where characters enter.
+    pub fn spawn_point(&self) -> Option<Vec2> {
+        self.portals
+            .iter()
+            .filter(|item| item.pn == "sp")
+            .map(|item| item.position)
+            .reduce(|acc, item| {
+                if item.length() > acc.length() {
+                    item
+                } else {
+                    acc
+                }
+            })
+    }
+
+    /// The area the camera may show, from the map's `VR*` bounds or, for maps
+    /// without them, the footholds' extent.
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        let info = &self.info;
+        if info.vr_right > info.vr_left && info.vr_bottom > info.vr_top {
+            return (
+                vec2(info.vr_left as f32, info.vr_top as f32),
+                vec2(info.vr_right as f32, info.vr_bottom as f32),
+            );
+        }
+        let (min, max) = self.footholds.values().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), item| {
+                (
+                    min.min(item.start).min(item.end),
+                    max.max(item.start).max(item.end),
+                )
+            },
+        );
+        if min.x > max.x {
+            return (Vec2::ZERO, Vec2::ZERO);
+        }
+        // room for the scenery above the highest platform
+        (min - vec2(100.0, 400.0), max + vec2(100.0, 100.0))
+    }
+
     pub fn new(root: &Node, name: &str) -> Result<Self, Error> {
         let map_img = root
             .at_path(&format!("Map/Map/Map{}/{name}.img", &name[0..1]))
@@ -273,13 +517,19 @@ impl Map {
                         String::from(item.get("l2"))
                     );
 
-                    let sprites: Vec<Sprite> = root.at_path(&path).unwrap().into();
+                    let animation = root.at_path(&path).unwrap();
+                    let sprites: Vec<Sprite> = animation.clone().into();
                     objects.push(MapObject {
                         id,
                         flip,
                         position: vec2(x as f32, y as f32),
                         z: z,
-                        timer: Timer::new(sprites.iter().map(|item| item.delay as f32).collect()),
+                        timer: Timer::with_playback(
+                            sprites.iter().map(|item| item.delay as f32).collect(),
+                            sprite::playback(&animation),
+                        ),
+                        motion: ObjectMotion::new(&animation),
+                        elapsed: 0.0,
                         sprites,
                     });
                 }
@@ -354,15 +604,27 @@ impl Map {
                 )
             })
             .collect();
+        let mob: HashMap<String, Mob> = life
+            .iter()
+            .filter(|item| item.r#type == "m")
+            .filter_map(|item| Some((item.id.to_string(), Mob::new(root, &item.id).ok()?)))
+            .collect();
 
         Ok(Self {
+            id: name.parse().unwrap_or_default(),
             life,
             npc,
+            mob,
             backgrounds,
             layers,
             footholds,
             portals: map_img.get("portal").into(),
+            ladders: map_img
+                .try_get("ladderRope")
+                .map(Into::into)
+                .unwrap_or_default(),
             info: map_img.get("info").into(),
+            minimap: map_img.try_get("miniMap").map(Into::into),
             portal_timer: Timer::new((1..helper.pv.len()).into_iter().map(|_| 100.0).collect()),
             helper,
         })
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use crate::wz::Node;
+
+#[derive(Debug, Default, Clone)]
+pub struct MapName {
+    pub street: String,
+    pub map: String,
+}
+
+impl From<Node> for MapName {
+    fn from(node: Node) -> Self {
+        Self {
+            street: node
+                .try_get("streetName")
+                .map(Into::into)
+                .unwrap_or_default(),
+            map: node.try_get("mapName").map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+/// Map names from `String/Map.img`, which groups them by region.
+#[derive(Default)]
+pub struct MapNames {
+    pub names: HashMap<i32, MapName>,
+}
+
+impl MapNames {
+    pub fn new(root: &Node) -> Self {
+        let Ok(node) = root.at_path("String/Map.img") else {
+            return Self::default();
+        };
+        let names = node
+            .children()
+            .into_values()
+            .flat_map(|region| {
+                let names: Vec<(i32, MapName)> = region.into();
+                names
+            })
+            .collect();
+        Self { names }
+    }
+
+    pub fn get(&self, id: i32) -> Option<&MapName> {
+        self.names.get(&id)
+    }
+}
@@ -0,0 +1,187 @@
+use glam::{vec2, Vec2};
+use sdl_sys::SDL_FRect;
+
+use super::{Drawable, Map, MapBackground};
+use crate::draw_queue::DrawQueue;
+use crate::sdl::{DrawParams, Renderer};
+
+/// Debug layers drawn over the map.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MapOverlay {
+    pub footholds: bool,
+    pub portals: bool,
+    pub ladders: bool,
+}
+
+impl MapBackground {
+    /// Queues the background for a view of `size` at `camera`, repeating it
+    /// according to its tiling type.
+    pub fn submit(&mut self, queue: &mut DrawQueue, z: i32, camera: Vec2, size: Vec2, delta: f32) {
+        let layer = if self.front {
+            DrawQueue::FOREGROUND
+        } else {
+            DrawQueue::BACKGROUND
+        };
+        let offset = camera + size / 2.0;
+        match self.r#type {
+            4 | 6 => {
+                self.offset_x += self.rx as f32 * 5.0 * delta / 1000.0;
+                self.offset_y = self.y + offset.y * (self.ry + 100) as f32 / 100.0;
+            }
+            5 | 7 => {
+                self.offset_x = self.x + offset.x * (self.rx + 100) as f32 / 100.0;
+                self.offset_y += self.ry as f32 * 5.0 * delta / 1000.0;
+            }
+            _ => {
+                self.offset_x = self.x + offset.x * (self.rx + 100) as f32 / 100.0;
+                self.offset_y = self.y + offset.y * (self.ry + 100) as f32 / 100.0;
+            }
+        }
+
+        let sprite = match &mut self.sprite {
+            Drawable::Sprite(sprite) => sprite,
+            Drawable::SpriteAnimation(animation) => animation.tick(delta),
+            Drawable::Placeholder(_) => return,
+        };
+        let params = DrawParams {
+            alpha: sprite.alpha * self.a / 255,
+            flip: self.flip,
+            ..Default::default()
+        };
+        let w = sprite.image.width() as f32;
+        let h = sprite.image.height() as f32;
+        let cw = if self.cx > 0 { self.cx as f32 } else { w };
+        let ch = if self.cy > 0 { self.cy as f32 } else { h };
+
+        let x = self.offset_x;
+        let y = self.offset_y;
+        let lb = x - sprite.origin.x;
+        let rb = lb + w;
+        let tb = y - sprite.origin.y;
+        let bb = tb + h;
+
+        let hs = f32::ceil((camera.x - rb) / cw) as i32;
+        let he = f32::ceil((camera.x + size.x - rb) / cw) as i32 + 1;
+
+        let vs = f32::ceil((camera.y - bb) / ch) as i32;
+        let ve = f32::ceil((camera.y + size.y - bb) / ch) as i32 + 1;
+
+        match self.r#type {
+            1 | 4 => {
+                for i in hs..he {
+                    queue.sprite_with(
+                        layer,
+                        z,
+                        sprite,
+                        vec2(x + i as f32 * cw, y) - camera,
+                        params,
+                    );
+                }
+            }
+            2 | 5 => {
+                for i in vs..ve {
+                    queue.sprite_with(
+                        layer,
+                        z,
+                        sprite,
+                        vec2(x, y + i as f32 * ch) - camera,
+                        params,
+                    );
+                }
+            }
+            3 | 6 | 7 => {
+                for i in vs..ve {
+                    for j in hs..he {
+                        queue.sprite_with(
+                            layer,
+                            z,
+                            sprite,
+                            vec2(x + j as f32 * cw, y + i as f32 * ch) - camera,
+                            params,
+                        );
+                    }
+                }
+            }
+            _ => {
+                queue.sprite_with(layer, z, sprite, vec2(x, y) - camera, params);
+            }
+        }
+    }
+}
+
+impl Map {
+    /// Queues the scenery as seen by a view of `size` at `camera`: both
+    /// background planes and every layer's tiles and objects by `z`. Portals
+    /// and life are entities, see [`crate::ecs`].
+    pub fn submit(&mut self, queue: &mut DrawQueue, camera: Vec2, size: Vec2, delta: f32) {
+        for (index, item) in self.backgrounds.iter_mut().enumerate() {
+            item.submit(queue, index as i32, camera, size, delta);
+        }
+
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            let index = index as i32;
+            for item in &mut layer.objects {
+                item.timer.tick(delta);
+                item.elapsed += delta;
+                let sprite = &item.sprites[item.timer.index];
+                let rotation = item.motion.rotation(item.elapsed);
+                let params = DrawParams {
+                    alpha: sprite.alpha_at(item.timer.progress()),
+                    flip: item.flip,
+                    // mirrored objects turn the other way
+                    rotation: if item.flip { -rotation } else { rotation },
+                    ..Default::default()
+                };
+                let position = item.position + item.motion.offset(item.elapsed) - camera;
+                queue.sprite_with(index, item.z, sprite, position, params);
+            }
+
+            for item in &mut layer.tiles {
+                queue.sprite(
+                    index,
+                    item.tile.z,
+                    &item.tile,
+                    item.position - camera,
+                    false,
+                );
+            }
+        }
+    }
+
+    pub fn draw_overlay(&self, renderer: &mut Renderer, camera: Vec2, overlay: MapOverlay) {
+        if overlay.footholds {
+            for item in self.footholds.values() {
+                renderer.render_line(item.start - camera, item.end - camera, [255, 0, 0, 255]);
+            }
+        }
+        if overlay.ladders {
+            for item in &self.ladders {
+                let color = if item.l {
+                    [255, 200, 0, 255]
+                } else {
+                    [0, 200, 255, 255]
+                };
+                renderer.render_line(
+                    vec2(item.x as f32, item.y1 as f32) - camera,
+                    vec2(item.x as f32, item.y2 as f32) - camera,
+                    color,
+                );
+            }
+        }
+        if overlay.portals {
+            for item in &self.portals {
+                let position = item.position - camera;
+                renderer.render_rect(
+                    &SDL_FRect {
+                        x: position.x - 15.0,
+                        y: position.y - 60.0,
+                        w: 30.0,
+                        h: 60.0,
+                    },
+                    [0, 255, 0, 255],
+                );
+                renderer.draw_text(&item.pn, position + vec2(-15.0, 4.0));
+            }
+        }
+    }
+}
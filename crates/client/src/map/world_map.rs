@@ -1,5 +1,6 @@
 use glam::Vec2;
 use indexmap::IndexMap;
+use wz_reader::node::Error;
 
 use crate::{sprite::Sprite, wz::Node};
 
@@ -42,6 +43,10 @@ impl From<Node> for Link {
 }
 
 pub struct WorldMap {
+    /// The img under `Map/WorldMap`, without the extension.
+    pub name: String,
+    /// The world map this one is part of, empty for the top one.
+    pub parent_map: Option<String>,
     pub base_img: Sprite,
     pub map_list: IndexMap<String, Item>,
     pub map_link: IndexMap<String, Link>,
@@ -50,9 +55,32 @@ pub struct WorldMap {
 impl From<Node> for WorldMap {
     fn from(node: Node) -> Self {
         Self {
+            name: String::new(),
+            parent_map: node
+                .try_get("info")
+                .and_then(|info| info.try_get("parentMap"))
+                .map(Into::into)
+                .filter(|name: &String| !name.is_empty()),
             base_img: node.at_path("BaseImg/0").unwrap().into(),
             map_list: node.get("MapList").into(),
             map_link: node.get("MapLink").into(),
         }
     }
 }
+
+impl WorldMap {
+    pub fn new(root: &Node, name: &str) -> Result<Self, Error> {
+        let node = root.at_path(&format!("Map/WorldMap/{name}.img"))?;
+        Ok(Self {
+            name: name.to_string(),
+            ..node.into()
+        })
+    }
+
+    /// The spot with `map` among its maps.
+    pub fn spot_of(&self, map: i32) -> Option<&Item> {
+        self.map_list
+            .values()
+            .find(|item| item.map_no.values().any(|id| *id == map))
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use wz_reader::node::Error;
+
+use crate::{sprite::SpriteAnimation, wz::Node};
+
+pub struct MobInfo {
+    // walking speed in percent off the base, -100 stands still
+    pub speed: i32,
+    // id of the mob whose animations this one reuses
+    pub link: Option<String>,
+    // dealt on touch, when `body_attack` is set
+    pub pa_damage: i32,
+    pub body_attack: i32,
+}
+
+impl From<Node> for MobInfo {
+    fn from(node: Node) -> Self {
+        Self {
+            speed: node.try_get("speed").map(Into::into).unwrap_or(0),
+            link: node.try_get("link").map(Into::into),
+            pa_damage: node.try_get("PADamage").map(Into::into).unwrap_or(0),
+            body_attack: node.try_get("bodyAttack").map(Into::into).unwrap_or(0),
+        }
+    }
+}
+
+pub struct Mob {
+    pub info: MobInfo,
+    pub actions: Arc<HashMap<String, SpriteAnimation>>,
+}
+
+impl Mob {
+    // px per ms at speed 0
+    const BASE_SPEED: f32 = 0.07;
+
+    // Mob/<id>.img
+    pub fn new(root: &Node, id: &str) -> Result<Self, Error> {
+        let node = root.at_path(&format!("Mob/{id}.img"))?;
+        let info: MobInfo = node.get("info").into();
+        let frames = match &info.link {
+            Some(link) => root.at_path(&format!("Mob/{link}.img"))?,
+            None => node,
+        };
+        let actions = frames
+            .children()
+            .into_iter()
+            .filter(|(k, _)| k.as_str() != "info")
+            .map(|(k, v)| (k.to_string(), v.into()))
+            .collect();
+        Ok(Self {
+            info,
+            actions: Arc::new(actions),
+        })
+    }
+
+    /// Walking speed in px per ms.
+    pub fn speed(&self) -> f32 {
+        Self::BASE_SPEED * (100 + self.info.speed).max(0) as f32 / 100.0
+    }
+
+    /// Damage dealt to players running into it, 0 if it doesn't hurt.
+    pub fn touch_damage(&self) -> u32 {
+        if self.info.body_attack == 0 {
+            return 0;
+        }
+        self.info.pa_damage.max(0) as u32
+    }
+}
@@ -0,0 +1,127 @@
+use glam::Vec2;
+
+use crate::character::{AvatarFrame, AvatarPart, AvatarPartInfo};
+use crate::timer::Timer;
+use crate::wz::Node;
+
+pub struct MountInfo {
+    // bonus percentages on top of the rider's own stats
+    pub speed: i32,
+    pub jump: i32,
+}
+
+impl From<Node> for MountInfo {
+    fn from(node: Node) -> Self {
+        Self {
+            speed: node.try_get("speed").map(Into::into).unwrap_or(0),
+            jump: node.try_get("jump").map(Into::into).unwrap_or(0),
+        }
+    }
+}
+
+pub struct Mount {
+    pub id: String,
+    pub info: MountInfo,
+    // the taming mob itself first, then its saddle
+    pub parts: Vec<AvatarPart>,
+    pub action: String,
+    pub timer: Timer,
+}
+
+impl Mount {
+    /// The avatar action used for the rider while mounted.
+    pub const RIDING_ACTION: &'static str = "sit";
+
+    /// Loads `Character/TamingMob/<id>.img` together with the saddle's layers
+    /// for it, which live under `Character/TamingMob/<saddle>.img/<mob id>`.
+    /// `None` if there's no such taming mob.
+    pub fn new(root: &Node, id: &str, saddle: Option<&str>) -> Option<Self> {
+        let node = root
+            .at_path(&format!("Character/TamingMob/{id}.img"))
+            .ok()?;
+        let mut parts = vec![AvatarPart {
+            info: AvatarPartInfo {
+                slot: "Tm".to_string(),
+                vslot: "Tm".to_string(),
+                cash: false,
+            },
+            variant: AvatarPart::variants(&node),
+        }];
+        if let Some(saddle) = saddle {
+            let path = format!(
+                "Character/TamingMob/{saddle}.img/{}",
+                id.trim_start_matches('0')
+            );
+            if let Ok(node) = root.at_path(&path) {
+                parts.push(AvatarPart {
+                    info: AvatarPartInfo {
+                        slot: "Sd".to_string(),
+                        vslot: "Sd".to_string(),
+                        cash: false,
+                    },
+                    variant: AvatarPart::variants(&node),
+                });
+            }
+        }
+
+        let mut mount = Self {
+            id: id.to_string(),
+            info: node.get("info").into(),
+            parts,
+            action: "stand1".to_string(),
+            timer: Timer::new(vec![]),
+        };
+        mount.timer = Timer::new(mount.delays(&mount.action));
+        Some(mount)
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        1.0 + self.info.speed as f32 / 100.0
+    }
+
+    pub fn jump_scale(&self) -> f32 {
+        1.0 + self.info.jump as f32 / 100.0
+    }
+
+    fn delays(&self, action: &str) -> Vec<f32> {
+        self.parts[0]
+            .variant
+            .get(action)
+            .map(|frames| {
+                frames
+                    .iter()
+                    .map(|frame| frame.delay.unwrap_or(100) as f32)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Switches to `action` if the mount has it, keeping the current one
+    /// otherwise.
+    pub fn set_action(&mut self, action: &str) {
+        if self.action == action || !self.parts[0].variant.contains_key(action) {
+            return;
+        }
+        self.action = action.to_string();
+        self.timer = Timer::new(self.delays(action));
+    }
+
+    pub fn tick(&mut self, delta: f32) -> bool {
+        self.timer.tick(delta)
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = &AvatarFrame> {
+        self.parts.iter().filter_map(|part| {
+            part.variant
+                .get(&self.action)
+                .and_then(|frames| frames.get(self.timer.index))
+        })
+    }
+
+    /// Where the rider's navel sits, relative to the mount's origin.
+    pub fn navel(&self) -> Option<Vec2> {
+        self.frames()
+            .flat_map(|frame| frame.parts.values())
+            .find_map(|part| part.map.get("navel").copied())
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::{sprite::SpriteAnimation, wz::Node};
 
@@ -16,7 +17,7 @@ impl From<Node> for NPCInfo {
 
 pub struct Npc {
     pub info: NPCInfo,
-    pub actions: HashMap<String, SpriteAnimation>,
+    pub actions: Arc<HashMap<String, SpriteAnimation>>,
 }
 
 impl From<Node> for Npc {
@@ -28,6 +29,9 @@ impl From<Node> for Npc {
             .filter(|(k, _)| k.as_str() != "info")
             .map(|(k, v)| (k.to_string(), v.into()))
             .collect();
-        Self { info, actions }
+        Self {
+            info,
+            actions: Arc::new(actions),
+        }
     }
 }
@@ -0,0 +1,318 @@
+use glam::{vec2, Vec2};
+use image::{DynamicImage, Rgba, RgbaImage};
+use std::sync::Arc;
+
+use crate::draw_queue::{DrawCommand, DrawQueue};
+use crate::sdl::{DrawParams, NineGridTexture, Renderer, TextStyle};
+use crate::sprite::Sprite;
+use crate::wz::Node;
+
+// `clr` entries are stored as signed ARGB
+fn color(value: i32) -> [u8; 4] {
+    let [a, r, g, b] = (value as u32).to_be_bytes();
+    [r, g, b, a]
+}
+
+pub enum NameTagFrame {
+    Sprites { w: Sprite, c: Sprite, e: Sprite },
+    NineGrid(NineGridTexture),
+}
+
+pub struct NameTag {
+    pub frame: NameTagFrame,
+    pub color: [u8; 4],
+}
+
+impl From<Node> for NameTag {
+    fn from(node: Node) -> Self {
+        Self {
+            frame: NameTagFrame::Sprites {
+                w: node.get("w").into(),
+                c: node.get("c").into(),
+                e: node.get("e").into(),
+            },
+            color: node
+                .try_get("clr")
+                .map(|clr| color(clr.into()))
+                .unwrap_or([255, 255, 255, 255]),
+        }
+    }
+}
+
+impl NameTag {
+    /// The `UI/NameTag.img` style `style`, or [`NameTag::fallback`] when the
+    /// data doesn't have it.
+    pub fn load(root: &Node, style: &str) -> Self {
+        root.at_path(&format!("UI/NameTag.img/{style}"))
+            .ok()
+            .filter(|node| ["w", "c", "e"].iter().all(|part| node.has(part)))
+            .map_or_else(Self::fallback, Into::into)
+    }
+
+    /// The plain translucent box used when no `UI/NameTag.img` style is set.
+    pub fn fallback() -> Self {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(3, 3, Rgba([0, 0, 0, 160])));
+        Self {
+            frame: NameTagFrame::NineGrid(NineGridTexture {
+                image: Arc::new(image),
+                left_width: 1,
+                middle_width: 1,
+                right_width: 1,
+                top_height: 1,
+                middle_height: 1,
+                bottom_height: 1,
+            }),
+            color: [255, 255, 255, 255],
+        }
+    }
+
+    /// Draws `text` centered below `position`, usually the character's feet.
+    pub fn draw(&self, renderer: &mut Renderer, text: &str, position: Vec2) {
+        if text.is_empty() {
+            return;
+        }
+        let texture = renderer.text(
+            text,
+            TextStyle {
+                color: self.color,
+                ..Default::default()
+            },
+        );
+        let padding = vec2(3.0, 2.0);
+        let left = (position.x - texture.size.x / 2.0).round();
+        let top = position.y + 2.0;
+
+        match &self.frame {
+            NameTagFrame::NineGrid(grid) => {
+                renderer.draw_nine_grid(
+                    grid,
+                    vec2(left, top) - padding,
+                    texture.size + padding * 2.0,
+                    255,
+                );
+            }
+            NameTagFrame::Sprites { w, c, e } => {
+                let right = left + texture.size.x;
+                renderer.draw(w, vec2(left, top));
+                let mut x = left;
+                while x < right {
+                    renderer.draw(c, vec2(x, top));
+                    x += c.size.x.max(1.0);
+                }
+                renderer.draw(e, vec2(right, top));
+            }
+        }
+
+        renderer.render_texture(
+            &texture,
+            vec2(left, top),
+            Vec2::ZERO,
+            None,
+            DrawParams::default(),
+        );
+    }
+}
+
+pub struct ChatBalloonStyle {
+    pub frame: NineGridTexture,
+    pub arrow: Sprite,
+    pub color: [u8; 4],
+}
+
+impl ChatBalloonStyle {
+    // UI/ChatBalloon.img/<style>
+    pub fn new(node: Node) -> Self {
+        let image = |name: &str| -> Arc<DynamicImage> { node.get(name).into() };
+        let frame = NineGridTexture::new_filled(
+            (
+                &image("nw"),
+                &image("n"),
+                &image("ne"),
+                &image("w"),
+                &image("e"),
+                &image("sw"),
+                &image("s"),
+                &image("se"),
+            ),
+            &image("c"),
+        );
+        Self {
+            frame,
+            arrow: node.get("arrow").into(),
+            color: node
+                .try_get("clr")
+                .map(|clr| color(clr.into()))
+                .unwrap_or([0, 0, 0, 255]),
+        }
+    }
+}
+
+pub struct ChatBalloon {
+    pub text: String,
+    pub elapsed: f32,
+    pub lifetime: f32,
+}
+
+impl ChatBalloon {
+    const FADE: f32 = 500.0;
+    const MAX_WIDTH: u32 = 120;
+
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            elapsed: 0.0,
+            lifetime: 5000.0,
+        }
+    }
+
+    /// Returns `false` once the balloon has faded out.
+    pub fn tick(&mut self, delta: f32) -> bool {
+        self.elapsed += delta;
+        self.elapsed < self.lifetime
+    }
+
+    pub fn alpha(&self) -> i32 {
+        let remaining = self.lifetime - self.elapsed;
+        ((remaining / Self::FADE).clamp(0.0, 1.0) * 255.0) as i32
+    }
+
+    /// Draws the balloon with its arrow pointing at `position`, usually the
+    /// top of the character's head.
+    pub fn draw(&self, style: &ChatBalloonStyle, renderer: &mut Renderer, position: Vec2) {
+        let alpha = self.alpha();
+        let texture = renderer.text(
+            &self.text,
+            TextStyle {
+                color: style.color,
+                max_width: Self::MAX_WIDTH,
+                ..Default::default()
+            },
+        );
+        let border = style.frame.border_size();
+        let size = texture.size + border;
+        let arrow_height = style.arrow.size.y - style.arrow.origin.y;
+        let offset = vec2(
+            position.x - size.x / 2.0,
+            position.y - size.y - arrow_height,
+        )
+        .round();
+
+        renderer.draw_nine_grid(&style.frame, offset, size, alpha);
+        renderer.draw_alpha(&style.arrow, vec2(position.x, offset.y + size.y), alpha);
+        renderer.render_texture(
+            &texture,
+            offset + vec2(style.frame.left_width as f32, style.frame.top_height as f32),
+            Vec2::ZERO,
+            None,
+            DrawParams {
+                alpha,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+// Effect/BasicEff.img/NoRed0 holds the regular digits and NoRed1 the larger
+// ones used for the leading digit.
+pub struct DamageFont {
+    pub digits: Vec<Sprite>,
+    pub leading: Vec<Sprite>,
+}
+
+impl DamageFont {
+    pub fn new(node: Node, name: &str) -> Self {
+        Self {
+            digits: node.get(&format!("{name}0")).into(),
+            leading: node.get(&format!("{name}1")).into(),
+        }
+    }
+}
+
+pub struct DamageNumber {
+    pub value: u32,
+    pub position: Vec2,
+    pub elapsed: f32,
+}
+
+pub struct DamageNumbers {
+    pub font: DamageFont,
+    pub items: Vec<DamageNumber>,
+}
+
+impl DamageNumbers {
+    const LIFETIME: f32 = 1000.0;
+    const RISE: f32 = 40.0;
+    const OVERLAP: f32 = 8.0;
+
+    pub fn new(font: DamageFont) -> Self {
+        Self {
+            font,
+            items: vec![],
+        }
+    }
+
+    /// Spawns `value` above `position`, stacking on numbers that are still
+    /// rising from the same spot.
+    pub fn spawn(&mut self, value: u32, position: Vec2) {
+        let stacked = self
+            .items
+            .iter()
+            .filter(|item| item.position.x == position.x && item.elapsed < Self::LIFETIME / 2.0)
+            .count();
+        self.items.push(DamageNumber {
+            value,
+            position: position - vec2(0.0, stacked as f32 * 30.0),
+            elapsed: 0.0,
+        });
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        for item in &mut self.items {
+            item.elapsed += delta;
+        }
+        self.items.retain(|item| item.elapsed < Self::LIFETIME);
+    }
+
+    pub fn submit(&self, queue: &mut DrawQueue, camera: Vec2) {
+        for item in &self.items {
+            let progress = item.elapsed / Self::LIFETIME;
+            let alpha = ((1.0 - progress) * 2.0).clamp(0.0, 1.0) * 255.0;
+            let digits: Vec<&Sprite> = item
+                .value
+                .to_string()
+                .bytes()
+                .enumerate()
+                .map(|(index, digit)| {
+                    let font = if index == 0 {
+                        &self.font.leading
+                    } else {
+                        &self.font.digits
+                    };
+                    &font[(digit - b'0') as usize]
+                })
+                .collect();
+            let width: f32 = digits
+                .iter()
+                .map(|sprite| sprite.size.x - Self::OVERLAP)
+                .sum();
+            let mut x = item.position.x - width / 2.0;
+            let y = item.position.y - progress * Self::RISE;
+            for sprite in digits {
+                queue.push(
+                    DrawQueue::EFFECT,
+                    0,
+                    DrawCommand {
+                        image: sprite.image.clone(),
+                        origin: sprite.origin,
+                        position: vec2(x + sprite.origin.x, y) - camera,
+                        params: DrawParams {
+                            alpha: alpha as i32,
+                            ..Default::default()
+                        },
+                    },
+                );
+                x += sprite.size.x - Self::OVERLAP;
+            }
+        }
+    }
+}
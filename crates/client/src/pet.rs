@@ -0,0 +1,277 @@
+use glam::{vec2, Vec2};
+use std::collections::HashMap;
+use wz_reader::node::Error;
+
+use crate::draw_queue::DrawQueue;
+use crate::ecs::Transform;
+use crate::map::Map;
+use crate::overhead::{ChatBalloon, ChatBalloonStyle, NameTag};
+use crate::random::Random;
+use crate::sdl::Renderer;
+use crate::sprite::{Sprite, SpriteAnimation};
+use crate::wz::Node;
+
+pub struct PetInfo {
+    // fullness lost per minute
+    pub hungry: i32,
+}
+
+impl From<Node> for PetInfo {
+    fn from(node: Node) -> Self {
+        Self {
+            hungry: node.try_get("hungry").map(Into::into).unwrap_or(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PetState {
+    Idle,
+    Walk,
+    Jump { velocity: f32 },
+}
+
+pub struct Pet {
+    pub id: String,
+    pub name: String,
+    pub info: PetInfo,
+    pub actions: HashMap<String, SpriteAnimation>,
+    pub action: String,
+    pub state: PetState,
+    pub fullness: f32,
+    pub balloon: Option<ChatBalloon>,
+    // said now and then, the second while hungry
+    lines: Vec<String>,
+    hungry_lines: Vec<String>,
+    speak_cooldown: f32,
+    random: Random,
+}
+
+impl Pet {
+    /// Out at once per owner.
+    pub const MAX: usize = 3;
+    const SPEED: f32 = 0.15;
+    const JUMP: f32 = 0.65;
+    const GRAVITY: f32 = 0.002;
+    const TELEPORT: f32 = 400.0;
+
+    // Item/Pet/<id>.img
+    pub fn new(root: &Node, id: &str) -> Result<Self, Error> {
+        let node = root.at_path(&format!("Item/Pet/{id}.img"))?;
+        let actions: HashMap<String, SpriteAnimation> = node
+            .children()
+            .into_iter()
+            .filter(|(k, _)| k.as_str() != "info")
+            .map(|(k, v)| (k.to_string(), v.into()))
+            .collect();
+        let name = root
+            .at_path(&format!("String/Pet.img/{id}/name"))
+            .map(String::from)
+            .unwrap_or_else(|_| id.to_string());
+        // String/PetDialog.img/<id>/<command> has `s` for what the pet says
+        // when it does as told and `f` for when it refuses, as hungry pets
+        // do, each `|`-separated
+        let mut lines = vec![];
+        let mut hungry_lines = vec![];
+        if let Ok(dialog) = root.at_path(&format!("String/PetDialog.img/{id}")) {
+            for (_, command) in dialog.children() {
+                for (key, lines) in [("s", &mut lines), ("f", &mut hungry_lines)] {
+                    let Some(text) = command.try_get(key).map(String::from) else {
+                        continue;
+                    };
+                    lines.extend(
+                        text.split('|')
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty())
+                            .map(ToString::to_string),
+                    );
+                }
+            }
+        }
+        Ok(Self {
+            id: id.to_string(),
+            name,
+            info: node.get("info").into(),
+            actions,
+            action: "stand0".to_string(),
+            state: PetState::Idle,
+            fullness: 100.0,
+            balloon: None,
+            lines,
+            hungry_lines,
+            speak_cooldown: 5000.0,
+            random: Random::seeded(id),
+        })
+    }
+
+    pub fn hungry(&self) -> bool {
+        self.fullness <= 0.0
+    }
+
+    pub fn feed(&mut self, amount: f32) {
+        self.fullness = (self.fullness + amount).min(100.0);
+    }
+
+    pub fn say(&mut self, text: &str) {
+        self.balloon = Some(ChatBalloon::new(text));
+    }
+
+    fn set_action(&mut self, action: &str) {
+        if self.action == action || !self.actions.contains_key(action) {
+            return;
+        }
+        self.action = action.to_string();
+        if let Some(animation) = self.actions.get_mut(action) {
+            animation.timer.reset();
+        }
+    }
+
+    /// Moves the pet towards `target` along the map's footholds, one step
+    /// of `step` ms.
+    pub fn update(&mut self, transform: &mut Transform, step: f32, target: Vec2, map: &Map) {
+        transform.prev_position = transform.position;
+        self.fullness = (self.fullness - self.info.hungry as f32 * step / 60000.0).max(0.0);
+
+        let distance = target - transform.position;
+        if distance.length() > Self::TELEPORT {
+            transform.position = target;
+            self.state = PetState::Idle;
+        }
+
+        let speed = if self.hungry() {
+            Self::SPEED / 2.0
+        } else {
+            Self::SPEED
+        };
+        let dx = target.x - transform.position.x;
+        let walking = dx.abs() > 10.0;
+        if walking {
+            transform.position.x += dx.signum() * (speed * step).min(dx.abs());
+            transform.flip = dx > 0.0;
+        }
+
+        match self.state {
+            PetState::Jump { velocity } => {
+                let velocity = velocity + Self::GRAVITY * step;
+                let next = transform.position.y + velocity * step;
+                match map.foothold_below(transform.position) {
+                    Some((_, y)) if velocity > 0.0 && next >= y => {
+                        transform.position.y = y;
+                        self.state = PetState::Idle;
+                    }
+                    None if next > target.y + Self::TELEPORT => {
+                        transform.position = target;
+                        self.state = PetState::Idle;
+                    }
+                    _ => {
+                        transform.position.y = next;
+                        self.state = PetState::Jump { velocity };
+                    }
+                }
+            }
+            PetState::Idle | PetState::Walk => {
+                match map.foothold_below(transform.position - vec2(0.0, 5.0)) {
+                    Some((_, y)) if y - transform.position.y <= 5.0 => {
+                        transform.position.y = y;
+                        if target.y < transform.position.y - 30.0 && dx.abs() < 100.0 {
+                            self.state = PetState::Jump {
+                                velocity: -Self::JUMP,
+                            };
+                        } else {
+                            self.state = if walking {
+                                PetState::Walk
+                            } else {
+                                PetState::Idle
+                            };
+                        }
+                    }
+                    // walked off an edge
+                    _ => self.state = PetState::Jump { velocity: 0.0 },
+                }
+            }
+        }
+
+        let action = match self.state {
+            PetState::Jump { .. } => "jump",
+            PetState::Walk => "move",
+            PetState::Idle if self.hungry() => "hungry",
+            PetState::Idle => "stand0",
+        };
+        self.set_action(action);
+        // the legs keep up with the pace
+        if let Some(animation) = self.actions.get_mut(action) {
+            animation.timer.speed = speed / Self::SPEED;
+        }
+
+        self.speak_cooldown -= step;
+        if self.speak_cooldown <= 0.0 {
+            self.speak_cooldown = self.random.range(8000.0, 20000.0);
+            let lines = if self.hungry() {
+                &self.hungry_lines
+            } else {
+                &self.lines
+            };
+            if !lines.is_empty() {
+                let line = lines[self.random.next() as usize % lines.len()].clone();
+                self.say(&line);
+            }
+        }
+        if let Some(balloon) = &mut self.balloon {
+            if !balloon.tick(step) {
+                self.balloon = None;
+            }
+        }
+    }
+
+    fn sprite(&self) -> Option<&Sprite> {
+        let animation = self.actions.get(&self.action)?;
+        animation.frames.get(animation.timer.index)
+    }
+
+    /// Advances the current action's frames by `delta` ms.
+    pub fn animate(&mut self, delta: f32) {
+        if let Some(animation) = self.actions.get_mut(&self.action) {
+            animation.timer.tick(delta);
+        }
+    }
+
+    /// Queues the pet at `position` on the map layer it stands on.
+    pub fn submit(
+        &self,
+        queue: &mut DrawQueue,
+        map: &Map,
+        position: Vec2,
+        flip: bool,
+        camera: Vec2,
+    ) {
+        let Some(sprite) = self.sprite() else {
+            return;
+        };
+        queue.sprite(
+            map.layer_at(position),
+            DrawQueue::LIFE_Z,
+            sprite,
+            position - camera,
+            flip,
+        );
+    }
+
+    /// Draws the name tag and chat balloon, over the whole map.
+    pub fn draw_overhead(
+        &self,
+        renderer: &mut Renderer,
+        name_tag: &NameTag,
+        balloon_style: &ChatBalloonStyle,
+        position: Vec2,
+    ) {
+        let Some(sprite) = self.sprite() else {
+            return;
+        };
+        let top = -sprite.origin.y;
+
+        name_tag.draw(renderer, &self.name, position);
+        if let Some(balloon) = &self.balloon {
+            balloon.draw(balloon_style, renderer, position + vec2(0.0, top));
+        }
+    }
+}
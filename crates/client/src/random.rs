@@ -0,0 +1,24 @@
+/// Xorshift, for behaviour that only has to look random.
+pub struct Random(pub u32);
+
+impl Random {
+    /// Seeded from `seed` so the same entity behaves the same every run.
+    pub fn seeded(seed: &str) -> Self {
+        Self(
+            seed.bytes()
+                .fold(0x9e3779b9, |acc, b| acc.rotate_left(5) ^ b as u32)
+                | 1,
+        )
+    }
+
+    pub fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (self.next() % 10000) as f32 / 10000.0 * (max - min)
+    }
+}
@@ -0,0 +1,400 @@
+use glam::{vec2, Vec2};
+use hecs::{Entity, World};
+use image::DynamicImage;
+use sdl_sys::{Event, SDL_Scancode};
+use std::sync::Arc;
+
+use super::{Context, Scene, Transition};
+use crate::draw_queue::DrawQueue;
+use crate::ecs::{self, systems};
+use crate::map::names::MapNames;
+use crate::map::world_map::WorldMap;
+use crate::map::Map;
+use crate::overhead;
+use crate::sdl::{NineGridTexture, Renderer};
+use crate::ui::{
+    Button, Chat, ChatKind, FlexItem, Frame, Image, Label, MinimapView, StatusBar, StatusView,
+    TextField, TextFieldAction, Tooltip, TooltipContent, Ui, Window, WindowPositions, WorldMapView,
+};
+use crate::wz::Node;
+
+// where window positions are kept between sessions
+const WINDOW_POSITIONS: &str = "./windows.ini";
+
+/// Playing on a map, with the player steered by the arrow keys.
+pub struct GameScene {
+    map: Map,
+    entities: World,
+    player: Entity,
+    queue: DrawQueue,
+    damage_numbers: overhead::DamageNumbers,
+    name_tag: overhead::NameTag,
+    balloon_style: overhead::ChatBalloonStyle,
+    // from the arrow keys
+    direction: Vec2,
+    camera: Vec2,
+    ui: Ui,
+    status_bar: StatusBar,
+    chat: Chat,
+    world_map: Window,
+    // for maps that have one
+    minimap: Option<Window>,
+    window_positions: WindowPositions,
+    // the view size the UI was laid out for
+    layout_size: Vec2,
+    tooltip: Tooltip,
+}
+
+impl GameScene {
+    pub fn new(root: &Node, map: Map, entities: World, player: Entity) -> Self {
+        let world_map_node = root.at_path("UI/UIWindow.img/WorldMap").unwrap();
+        let names = Arc::new(MapNames::new(root));
+
+        let mut ui = Ui::default();
+        // first, so windows stay on top of it
+        let status_bar = StatusBar::new(&mut ui, root);
+        let chat = Chat::new(&mut ui);
+        let world_map = Window::new(
+            &mut ui,
+            "WorldMap",
+            window_border(root),
+            Image::new(world_map_node.get("title").into()),
+            Button::from(root.at_path("UI/Basic.img/BtClose").unwrap()),
+            WorldMapView::new(
+                root,
+                WorldMap::new(root, "WorldMap").unwrap(),
+                names.clone(),
+            ),
+        );
+        if let Some(view) = ui.get_mut::<WorldMapView>(world_map.content) {
+            view.current_map = map.id;
+        }
+        WorldMapView::add_back_button(&mut ui, root, world_map.content);
+
+        let minimap = map.minimap.clone().map(|minimap| {
+            let name = names.get(map.id).map_or("", |name| name.map.as_str());
+            let mut window = Window::new(
+                &mut ui,
+                "MiniMap",
+                window_border(root),
+                Label::new(name, TooltipContent::TITLE),
+                Button::from(root.at_path("UI/Basic.img/BtClose").unwrap()),
+                MinimapView::new(root, minimap),
+            );
+            window.home = Some(Vec2::ZERO);
+            // collapsing leaves only the title bar
+            let content = window.content;
+            let collapse = |ui: &mut Ui| {
+                let visible = !ui.visible(content);
+                ui.set_visible(content, visible);
+            };
+            if let Ok(node) = root.at_path("UI/UIWindow.img/MiniMap/BtMin") {
+                let button = window.add_button(&mut ui, node.into());
+                ui.on_click(button, collapse);
+            }
+
+            if let Some(view) = ui.get_mut::<MinimapView>(window.content) {
+                for (_, (transform, _)) in entities.query::<(&ecs::Transform, &ecs::Npc)>().iter() {
+                    view.npcs.push(transform.position);
+                }
+                for (_, (transform, portal)) in
+                    entities.query::<(&ecs::Transform, &ecs::Portal)>().iter()
+                {
+                    if portal.name != "sp" {
+                        view.portals.push(transform.position);
+                    }
+                }
+            }
+            window
+        });
+
+        Self {
+            map,
+            entities,
+            player,
+            queue: DrawQueue::default(),
+            damage_numbers: overhead::DamageNumbers::new(overhead::DamageFont::new(
+                root.at_path("Effect/BasicEff.img").unwrap(),
+                "NoRed",
+            )),
+            name_tag: overhead::NameTag::load(root, "0"),
+            balloon_style: overhead::ChatBalloonStyle::new(
+                root.at_path("UI/ChatBalloon.img/0").unwrap(),
+            ),
+            direction: Vec2::ZERO,
+            camera: Vec2::ZERO,
+            ui,
+            status_bar,
+            chat,
+            world_map,
+            minimap,
+            window_positions: WindowPositions::load(WINDOW_POSITIONS),
+            layout_size: Vec2::ZERO,
+            tooltip: Tooltip::fallback(),
+        }
+    }
+
+    fn layout(&mut self, size: Vec2, renderer: &Renderer) {
+        let ui = &mut self.ui;
+        if let Some(frame) = ui.get_mut::<Frame>(self.world_map.frame) {
+            let border = frame.grid.border_size();
+            let content_size = vec2(640.0, 470.0).min(size - border);
+            ui.set_flex(self.world_map.content, FlexItem::fixed(content_size));
+        }
+        for window in std::iter::once(&self.world_map).chain(&self.minimap) {
+            window.fit(ui, renderer, size, &self.window_positions);
+        }
+        self.status_bar.fit(ui, size);
+        self.chat.fit(ui, size, size.y - self.status_bar.height());
+        ui.set_view(size);
+        ui.layout(renderer);
+    }
+
+    // after a drag, so windows open where they were left next time
+    fn save_window_positions(&mut self) {
+        let mut moved = false;
+        for window in std::iter::once(&self.world_map).chain(&self.minimap) {
+            moved |= self.window_positions.update(&self.ui, window);
+        }
+        if moved {
+            // losing positions isn't worth stopping the game for
+            let _ = self.window_positions.save();
+        }
+    }
+
+    fn toggle_minimap(&mut self) {
+        if let Some(window) = &self.minimap {
+            window.toggle(&mut self.ui);
+        }
+    }
+
+    // follows the characters on the map
+    fn update_minimap(&mut self) {
+        let Some(window) = &self.minimap else {
+            return;
+        };
+        let Some(view) = self.ui.get_mut::<MinimapView>(window.content) else {
+            return;
+        };
+        view.others.clear();
+        for (entity, (transform, _)) in self
+            .entities
+            .query::<(&ecs::Transform, &ecs::Player)>()
+            .iter()
+        {
+            if entity == self.player {
+                view.player = transform.position;
+            } else {
+                view.others.push(transform.position);
+            }
+        }
+    }
+
+    fn chat_focused(&self) -> bool {
+        self.ui.focus() == Some(self.chat.input)
+    }
+
+    // once the chat input was sent or let go
+    fn handle_chat(&mut self) {
+        let Some(input) = self.ui.get_mut::<TextField>(self.chat.input) else {
+            return;
+        };
+        match input.take_action() {
+            Some(TextFieldAction::Submit(text)) if !text.is_empty() => self.say(&text),
+            Some(_) => self.ui.set_focus(None),
+            None => {}
+        }
+    }
+
+    /// `/w name message` whispers and `/p message` goes to the party,
+    /// anything else is said out loud in a balloon.
+    fn say(&mut self, text: &str) {
+        let Ok(player) = self.entities.query_one_mut::<&mut ecs::Player>(self.player) else {
+            return;
+        };
+        let (ui, chat) = (&mut self.ui, &self.chat);
+        if let Some(rest) = text.strip_prefix("/w ") {
+            match rest.trim().split_once(' ') {
+                Some((to, message)) => {
+                    chat.push(ui, ChatKind::Whisper, format!("{to} << {message}"))
+                }
+                None => chat.push(ui, ChatKind::System, "Usage: /w <name> <message>"),
+            }
+        } else if let Some(message) = text.strip_prefix("/p ") {
+            chat.push(ui, ChatKind::Party, format!("{} : {message}", player.name));
+        } else if text.starts_with('/') {
+            chat.push(ui, ChatKind::System, format!("Unknown command: {text}"));
+        } else {
+            let line = format!("{} : {text}", player.name);
+            player.balloon = Some(overhead::ChatBalloon::new(&line));
+            chat.push(ui, ChatKind::General, line);
+        }
+    }
+
+    fn update_status_bar(&mut self) {
+        let Some(view) = self.ui.get_mut::<StatusView>(self.status_bar.status) else {
+            return;
+        };
+        if let Ok((player, stats)) = self
+            .entities
+            .query_one_mut::<(&ecs::Player, &ecs::Stats)>(self.player)
+        {
+            view.stats = *stats;
+            if view.name != player.name {
+                view.name = player.name.clone();
+            }
+        }
+    }
+}
+
+// the frame all windows share
+fn window_border(root: &Node) -> NineGridTexture {
+    let border: Vec<Arc<DynamicImage>> = root
+        .at_path("UI/UIWindow.img/WorldMap/Border")
+        .unwrap()
+        .into();
+    NineGridTexture::new((
+        &border[0], &border[1], &border[2], &border[3], &border[4], &border[5], &border[6],
+        &border[7],
+    ))
+}
+
+impl Scene for GameScene {
+    fn event(&mut self, ctx: &mut Context, event: &Event) -> Transition {
+        if self.ui.event(event) {
+            if let Event::MouseButtonUp { .. } = event {
+                self.save_window_positions();
+            }
+            self.handle_chat();
+            return Transition::None;
+        }
+        let direction = &mut self.direction;
+        match event {
+            Event::KeyDown { scancode, .. } => match *scancode {
+                SDL_Scancode::SDL_SCANCODE_LEFT => {
+                    direction.x = -1.0;
+                }
+                SDL_Scancode::SDL_SCANCODE_RIGHT => {
+                    direction.x = 1.0;
+                }
+                SDL_Scancode::SDL_SCANCODE_UP => {
+                    direction.y = -1.0;
+                }
+                SDL_Scancode::SDL_SCANCODE_DOWN => {
+                    direction.y = 1.0;
+                }
+                SDL_Scancode::SDL_SCANCODE_ESCAPE => return Transition::Pop,
+                SDL_Scancode::SDL_SCANCODE_RETURN | SDL_Scancode::SDL_SCANCODE_KP_ENTER => {
+                    self.ui.set_focus(Some(self.chat.input));
+                }
+                SDL_Scancode::SDL_SCANCODE_W => {
+                    self.world_map.toggle(&mut self.ui);
+                }
+                SDL_Scancode::SDL_SCANCODE_M => self.toggle_minimap(),
+                _ => {}
+            },
+            Event::KeyUp { scancode, .. } => {
+                let pressed = |scancode| ctx.keys.pressed(scancode);
+                match *scancode {
+                    SDL_Scancode::SDL_SCANCODE_LEFT => {
+                        direction.x = if pressed(SDL_Scancode::SDL_SCANCODE_RIGHT) {
+                            1.0
+                        } else {
+                            0.0
+                        };
+                    }
+                    SDL_Scancode::SDL_SCANCODE_RIGHT => {
+                        direction.x = if pressed(SDL_Scancode::SDL_SCANCODE_LEFT) {
+                            -1.0
+                        } else {
+                            0.0
+                        };
+                    }
+                    SDL_Scancode::SDL_SCANCODE_UP => {
+                        direction.y = if pressed(SDL_Scancode::SDL_SCANCODE_DOWN) {
+                            1.0
+                        } else {
+                            0.0
+                        };
+                    }
+                    SDL_Scancode::SDL_SCANCODE_DOWN => {
+                        direction.y = if pressed(SDL_Scancode::SDL_SCANCODE_UP) {
+                            -1.0
+                        } else {
+                            0.0
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn update(&mut self, _ctx: &mut Context, _delta: f32) -> Transition {
+        // keys let go while typing never reach the game
+        if self.chat_focused() {
+            self.direction = Vec2::ZERO;
+        }
+        systems::control(&mut self.entities, self.direction);
+        Transition::None
+    }
+
+    fn step(&mut self, _ctx: &mut Context, step: f32) -> Transition {
+        systems::ai(&mut self.entities, step);
+        systems::pets(&mut self.entities, &self.map, step);
+        systems::physics(&mut self.entities, &self.map, step);
+        for (damage, position) in systems::touch_damage(&mut self.entities, step) {
+            self.damage_numbers.spawn(damage, position);
+        }
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, delta: f32, alpha: f32) {
+        if let Ok(transform) = self.entities.query_one_mut::<&ecs::Transform>(self.player) {
+            self.camera = transform.interpolate(alpha) - ctx.size / 2.0;
+        }
+        let camera = self.camera;
+        let queue = &mut self.queue;
+
+        self.map.submit(queue, camera, ctx.size, delta);
+        systems::animate(&mut self.entities, &mut self.map, delta);
+        systems::npc_actions(&mut self.entities);
+
+        systems::submit(&self.entities, &self.map, queue, camera, alpha);
+
+        self.damage_numbers.tick(delta);
+        self.damage_numbers.submit(queue, camera);
+
+        queue.flush(ctx.renderer);
+        systems::draw_overhead(
+            &self.entities,
+            ctx.renderer,
+            &self.name_tag,
+            &self.balloon_style,
+            camera,
+            alpha,
+        );
+
+        self.update_minimap();
+        self.update_status_bar();
+        if self.layout_size != ctx.size || self.ui.needs_layout() {
+            self.layout_size = ctx.size;
+            self.layout(ctx.size, ctx.renderer);
+        }
+        self.ui.tick(delta);
+        self.ui.draw(ctx.renderer);
+        if self.chat_focused() {
+            let rect = self.ui.absolute_rect(self.chat.input);
+            let caret = self
+                .ui
+                .get_mut::<TextField>(self.chat.input)
+                .map_or(0.0, |input| input.caret());
+            ctx.text_input = Some((rect, caret));
+        }
+        self.tooltip.hover(self.ui.tooltip());
+        self.tooltip.tick(delta);
+        self.tooltip.draw(ctx.renderer, ctx.mouse, ctx.size);
+    }
+}
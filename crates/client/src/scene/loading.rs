@@ -0,0 +1,128 @@
+use glam::vec2;
+use hecs::{Entity, World};
+use sdl_sys::SDL_FRect;
+
+use super::{CharacterInfo, Context, GameScene, Scene, Transition};
+use crate::character::ZMap;
+use crate::ecs;
+use crate::map::Map;
+use crate::pet::Pet;
+use std::sync::Arc;
+
+/// Loads a character's map a stage per frame, showing how far it got.
+pub struct LoadingScene {
+    character: CharacterInfo,
+    stage: usize,
+    // the current stage's label has been on screen
+    shown: bool,
+    map: Option<Map>,
+    entities: World,
+    player: Option<Entity>,
+}
+
+impl LoadingScene {
+    const STAGES: [&'static str; 4] = [
+        "Loading map",
+        "Packing textures",
+        "Spawning characters",
+        "Loading interface",
+    ];
+
+    pub fn new(character: CharacterInfo) -> Self {
+        Self {
+            character,
+            stage: 0,
+            shown: false,
+            map: None,
+            entities: World::new(),
+            player: None,
+        }
+    }
+}
+
+impl Scene for LoadingScene {
+    fn update(&mut self, ctx: &mut Context, _delta: f32) -> Transition {
+        if !self.shown {
+            return Transition::None;
+        }
+        match self.stage {
+            0 => {
+                self.map = Some(Map::new(ctx.root, &self.character.map).unwrap());
+            }
+            1 => {
+                let map = self.map.as_ref().unwrap();
+                ctx.renderer.load_atlas(&map.atlas());
+            }
+            2 => {
+                let map = self.map.as_ref().unwrap();
+                let z_map = Arc::new(ZMap::new(
+                    ctx.root.at_path("zmap.img").unwrap(),
+                    ctx.root.at_path("smap.img").unwrap(),
+                ));
+                let player = ecs::Player {
+                    avatar: self.character.avatar(ctx.root, z_map),
+                    name: self.character.name.clone(),
+                    balloon: None,
+                    speed: 2.4,
+                    invincible: 0.0,
+                };
+                ecs::spawn_map(&mut self.entities, map);
+                let position = map.spawn_point().unwrap_or_default();
+                let player = ecs::spawn_player(&mut self.entities, map, player, position);
+                for id in &self.character.pets {
+                    let pet = match Pet::new(ctx.root, id) {
+                        Ok(pet) => pet,
+                        Err(err) => {
+                            eprintln!("pet {id}: {err:?}");
+                            continue;
+                        }
+                    };
+                    if ecs::spawn_pet(&mut self.entities, player, pet).is_err() {
+                        eprintln!("pet {id}: {} are out already", Pet::MAX);
+                    }
+                }
+                self.player = Some(player);
+            }
+            _ => {
+                let scene = GameScene::new(
+                    ctx.root,
+                    self.map.take().unwrap(),
+                    std::mem::take(&mut self.entities),
+                    self.player.unwrap(),
+                );
+                return Transition::Replace(Box::new(scene));
+            }
+        }
+        self.stage += 1;
+        self.shown = false;
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _delta: f32, _alpha: f32) {
+        let width = 300.0;
+        let position = ctx.size / 2.0 - vec2(width / 2.0, 0.0);
+        let progress = self.stage as f32 / Self::STAGES.len() as f32;
+        let label = Self::STAGES[self.stage.min(Self::STAGES.len() - 1)];
+        ctx.renderer
+            .draw_text(&format!("{label}..."), position - vec2(0.0, 30.0));
+        ctx.renderer.render_rect(
+            &SDL_FRect {
+                x: position.x,
+                y: position.y,
+                w: width,
+                h: 12.0,
+            },
+            [255, 255, 255, 255],
+        );
+        ctx.renderer.fill_rect(
+            &SDL_FRect {
+                x: position.x + 2.0,
+                y: position.y + 2.0,
+                w: (width - 4.0) * progress,
+                h: 8.0,
+            },
+            [255, 200, 0, 255],
+        );
+        self.shown = true;
+    }
+}
@@ -0,0 +1,136 @@
+use glam::{vec2, Vec2};
+use sdl_sys::{Event, SDL_Scancode};
+
+use super::{Context, Scene, SelectScene, Transition};
+use crate::sprite::Sprite;
+use crate::ui::Button;
+use crate::wz::Node;
+
+/// The title screen from `UI/Login.img`, taking an ID and password before
+/// character selection. There's no server yet, so any ID gets in.
+pub struct LoginScene {
+    signboard: Option<Sprite>,
+    login: Option<Button>,
+    quit: Option<Button>,
+    account: String,
+    password: String,
+    // typing goes to the password rather than the ID
+    password_focus: bool,
+    pressed: bool,
+}
+
+impl LoginScene {
+    pub fn new(root: &Node) -> Self {
+        let title = root.at_path("UI/Login.img/Title").ok();
+        let child = |name: &str| title.as_ref()?.at_path(name).ok();
+        Self {
+            signboard: child("signboard").map(Into::into),
+            login: child("BtLogin").map(Into::into),
+            quit: child("BtQuit").map(Into::into),
+            account: String::new(),
+            password: String::new(),
+            password_focus: false,
+            pressed: false,
+        }
+    }
+
+    fn login_position(size: Vec2) -> Vec2 {
+        size / 2.0 + vec2(70.0, -30.0)
+    }
+
+    fn quit_position(size: Vec2) -> Vec2 {
+        size / 2.0 + vec2(70.0, 20.0)
+    }
+
+    fn submit(&self, ctx: &Context) -> Transition {
+        if self.account.is_empty() {
+            return Transition::None;
+        }
+        Transition::Push(Box::new(SelectScene::new(ctx.root, &self.account)))
+    }
+}
+
+impl Scene for LoginScene {
+    fn event(&mut self, ctx: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::KeyDown {
+                scancode, keycode, ..
+            } => {
+                let field = if self.password_focus {
+                    &mut self.password
+                } else {
+                    &mut self.account
+                };
+                match *scancode {
+                    SDL_Scancode::SDL_SCANCODE_TAB => {
+                        self.password_focus = !self.password_focus;
+                    }
+                    SDL_Scancode::SDL_SCANCODE_BACKSPACE => {
+                        field.pop();
+                    }
+                    SDL_Scancode::SDL_SCANCODE_RETURN => return self.submit(ctx),
+                    SDL_Scancode::SDL_SCANCODE_ESCAPE => return Transition::Quit,
+                    // printable keys have their character as keycode, enough
+                    // for IDs until there's proper text input
+                    _ => {
+                        let c = char::from_u32(*keycode).filter(char::is_ascii_alphanumeric);
+                        if let Some(c) = c.filter(|_| field.len() < 12) {
+                            field.push(c);
+                        }
+                    }
+                }
+            }
+            Event::MouseButtonDown { .. } => {
+                self.pressed = true;
+            }
+            Event::MouseButtonUp { x, y, .. } => {
+                self.pressed = false;
+                let point = vec2(*x, *y);
+                let over = |button: &Option<Button>, position: Vec2| {
+                    button
+                        .as_ref()
+                        .is_some_and(|button| button.contains(position, point))
+                };
+                if over(&self.login, Self::login_position(ctx.size)) {
+                    return self.submit(ctx);
+                }
+                if over(&self.quit, Self::quit_position(ctx.size)) {
+                    return Transition::Quit;
+                }
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _delta: f32, _alpha: f32) {
+        let center = ctx.size / 2.0;
+        if let Some(signboard) = &self.signboard {
+            ctx.renderer.draw(signboard, center);
+        }
+
+        let cursor = |focus: bool| if focus { "|" } else { "" };
+        let account = format!("ID: {}{}", self.account, cursor(!self.password_focus));
+        let password = format!(
+            "PW: {}{}",
+            "*".repeat(self.password.len()),
+            cursor(self.password_focus)
+        );
+        ctx.renderer
+            .draw_text(&account, center + vec2(-120.0, -40.0));
+        ctx.renderer
+            .draw_text(&password, center + vec2(-120.0, -14.0));
+
+        for (button, position) in [
+            (&self.login, Self::login_position(ctx.size)),
+            (&self.quit, Self::quit_position(ctx.size)),
+        ] {
+            let frame = button
+                .as_ref()
+                .and_then(|button| button.frame(position, ctx.mouse, self.pressed));
+            if let Some(frame) = frame {
+                ctx.renderer.draw(frame, position);
+            }
+        }
+    }
+}
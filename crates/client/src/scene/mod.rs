@@ -0,0 +1,193 @@
+use glam::Vec2;
+use sdl_sys::{Event, KeyboardState, SDL_FRect};
+
+use crate::sdl::Renderer;
+use crate::ui::Rect;
+use crate::wz::Node;
+
+mod game;
+mod loading;
+mod login;
+mod select;
+
+pub use game::GameScene;
+pub use loading::LoadingScene;
+pub use login::LoginScene;
+pub use select::{CharacterInfo, SelectScene};
+
+/// What the scene stack does after a scene handled something.
+pub enum Transition {
+    None,
+    /// Covers the current scene, which resumes once this one pops.
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+    Quit,
+}
+
+/// Everything a scene gets from the window it runs in.
+pub struct Context<'a, 'r> {
+    pub root: &'a Node,
+    pub renderer: &'a mut Renderer<'r>,
+    /// The view in logical units.
+    pub size: Vec2,
+    /// The pointer in logical units.
+    pub mouse: Vec2,
+    pub keys: KeyboardState,
+    /// Set by a scene while a text field has the keyboard: the field's rect
+    /// and the caret's offset from its left edge. Text input, and the IME,
+    /// only run while it's set.
+    pub text_input: Option<(Rect, f32)>,
+}
+
+pub trait Scene {
+    /// Input, with pointer positions already in logical units.
+    fn event(&mut self, _ctx: &mut Context, _event: &Event) -> Transition {
+        Transition::None
+    }
+
+    /// Once per frame, `delta` ms after the last one.
+    fn update(&mut self, _ctx: &mut Context, _delta: f32) -> Transition {
+        Transition::None
+    }
+
+    /// One simulation step of `step` ms, see [`crate::game_loop::GameLoop`].
+    fn step(&mut self, _ctx: &mut Context, _step: f32) -> Transition {
+        Transition::None
+    }
+
+    /// Advances animations by `delta` ms and draws, `alpha` of a step past
+    /// the last one.
+    fn draw(&mut self, ctx: &mut Context, delta: f32, alpha: f32);
+}
+
+/// Fades to black, switches scenes, then fades back in.
+struct Fade {
+    elapsed: f32,
+    // applied once faded out
+    pending: Option<Transition>,
+}
+
+/// Scenes on top of each other, only the top one getting input and drawing.
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    fade: Option<Fade>,
+    quit: bool,
+}
+
+impl SceneStack {
+    // ms for each half of a fade
+    const FADE: f32 = 250.0;
+
+    pub fn new(scene: Box<dyn Scene>) -> Self {
+        Self {
+            scenes: vec![scene],
+            fade: None,
+            quit: false,
+        }
+    }
+
+    /// Set on [`Transition::Quit`] or when the last scene pops.
+    pub fn quit(&self) -> bool {
+        self.quit || self.scenes.is_empty()
+    }
+
+    // the top scene is frozen while fading out of it
+    fn active(&mut self) -> Option<&mut Box<dyn Scene>> {
+        if self
+            .fade
+            .as_ref()
+            .is_some_and(|fade| fade.pending.is_some())
+        {
+            return None;
+        }
+        self.scenes.last_mut()
+    }
+
+    pub fn event(&mut self, ctx: &mut Context, event: &Event) {
+        if let Some(scene) = self.active() {
+            let transition = scene.event(ctx, event);
+            self.transition(transition);
+        }
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, delta: f32) {
+        if let Some(fade) = &mut self.fade {
+            fade.elapsed += delta;
+            if fade.elapsed >= Self::FADE {
+                if let Some(transition) = fade.pending.take() {
+                    self.apply(transition);
+                }
+            }
+        }
+        if self
+            .fade
+            .as_ref()
+            .is_some_and(|fade| fade.elapsed >= Self::FADE * 2.0)
+        {
+            self.fade = None;
+        }
+        if let Some(scene) = self.active() {
+            let transition = scene.update(ctx, delta);
+            self.transition(transition);
+        }
+    }
+
+    pub fn step(&mut self, ctx: &mut Context, step: f32) {
+        if let Some(scene) = self.active() {
+            let transition = scene.step(ctx, step);
+            self.transition(transition);
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, delta: f32, alpha: f32) {
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.draw(ctx, delta, alpha);
+        }
+        if let Some(fade) = &self.fade {
+            let t = if fade.pending.is_some() {
+                fade.elapsed / Self::FADE
+            } else {
+                2.0 - fade.elapsed / Self::FADE
+            };
+            ctx.renderer.fill_rect(
+                &SDL_FRect {
+                    x: 0.0,
+                    y: 0.0,
+                    w: ctx.size.x,
+                    h: ctx.size.y,
+                },
+                [0, 0, 0, (t.clamp(0.0, 1.0) * 255.0) as u8],
+            );
+        }
+    }
+
+    fn transition(&mut self, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Quit => self.quit = true,
+            // one switch at a time
+            _ if self.fade.is_some() => {}
+            _ => {
+                self.fade = Some(Fade {
+                    elapsed: 0.0,
+                    pending: Some(transition),
+                })
+            }
+        }
+    }
+
+    fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            Transition::None | Transition::Quit => {}
+        }
+    }
+}
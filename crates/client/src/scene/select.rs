@@ -0,0 +1,297 @@
+use glam::{vec2, Vec2};
+use sdl_sys::{Event, SDL_Scancode};
+use std::sync::Arc;
+
+use super::{Context, LoadingScene, Scene, Transition};
+use crate::character::{Character, ZMap};
+use crate::mount::Mount;
+use crate::ui::Button;
+use crate::wz::Node;
+
+// cycled through when creating a character
+const HAIRS: [&str; 4] = [
+    "Hair/00030000",
+    "Hair/00030020",
+    "Hair/00030030",
+    "Hair/00031000",
+];
+const FACES: [&str; 4] = [
+    "Face/00020000",
+    "Face/00020001",
+    "Face/00020002",
+    "Face/00021000",
+];
+const OUTFIT: [&str; 5] = [
+    "00002000",
+    "00012000",
+    "Coat/01040036",
+    "Pants/01060026",
+    "Shoes/01071000",
+];
+
+/// A character to play, as the paths of its parts under `Character`.
+#[derive(Debug, Clone)]
+pub struct CharacterInfo {
+    pub name: String,
+    pub parts: Vec<String>,
+    /// The taming mob ridden and its saddle, ids under `Character/TamingMob`.
+    pub mount: Option<(String, Option<String>)>,
+    /// Pets out, as ids under `Item/Pet`.
+    pub pets: Vec<String>,
+    pub map: String,
+}
+
+impl CharacterInfo {
+    pub fn new(name: &str, hair: &str, face: &str) -> Self {
+        let mut parts: Vec<String> = OUTFIT.iter().map(ToString::to_string).collect();
+        parts.push(hair.to_string());
+        parts.push(face.to_string());
+        Self {
+            name: name.to_string(),
+            parts,
+            mount: None,
+            pets: vec![],
+            map: "002000000".to_string(),
+        }
+    }
+
+    /// Leaves out parts and a mount missing from the data.
+    pub fn avatar(&self, root: &Node, z_map: Arc<ZMap>) -> Character {
+        let parts = self
+            .parts
+            .iter()
+            .filter_map(|path| root.at_path(&format!("Character/{path}.img")).ok())
+            .collect();
+        let mut avatar = Character::new(parts, z_map);
+        if let Some((id, saddle)) = &self.mount {
+            avatar.set_mount(Mount::new(root, id, saddle.as_deref()));
+        }
+        avatar
+    }
+}
+
+struct Creation {
+    hair: usize,
+    face: usize,
+    avatar: Character,
+}
+
+impl Creation {
+    fn info(&self, name: &str) -> CharacterInfo {
+        CharacterInfo::new(name, HAIRS[self.hair], FACES[self.face])
+    }
+}
+
+/// Picks one of the account's characters, or makes a new one.
+pub struct SelectScene {
+    root: Node,
+    z_map: Arc<ZMap>,
+    account: String,
+    characters: Vec<(CharacterInfo, Character)>,
+    selected: usize,
+    creating: Option<Creation>,
+    select: Option<Button>,
+    new: Option<Button>,
+    delete: Option<Button>,
+    pressed: bool,
+}
+
+impl SelectScene {
+    pub fn new(root: &Node, account: &str) -> Self {
+        let z_map = Arc::new(ZMap::new(
+            root.at_path("zmap.img").unwrap(),
+            root.at_path("smap.img").unwrap(),
+        ));
+        let info = CharacterInfo::new(account, HAIRS[0], FACES[0]);
+        let avatar = info.avatar(root, z_map.clone());
+        let buttons = root.at_path("UI/Login.img/CharSelect").ok();
+        let button = |name: &str| buttons.as_ref()?.at_path(name).ok().map(Button::from);
+        let mut scene = Self {
+            root: root.clone(),
+            z_map,
+            account: account.to_string(),
+            characters: vec![(info, avatar)],
+            selected: 0,
+            creating: None,
+            select: button("BtSelect"),
+            new: button("BtNew"),
+            delete: button("BtDelete"),
+            pressed: false,
+        };
+        scene.pose(0);
+        scene
+    }
+
+    // the selected character walks in place
+    fn pose(&mut self, selected: usize) {
+        if let Some((_, avatar)) = self.characters.get_mut(self.selected) {
+            avatar.set_action("stand1");
+        }
+        self.selected = selected;
+        if let Some((_, avatar)) = self.characters.get_mut(self.selected) {
+            avatar.set_action("walk1");
+        }
+    }
+
+    fn buttons(&self, size: Vec2) -> [(&Option<Button>, Vec2); 3] {
+        let position = vec2(size.x - 120.0, size.y / 2.0);
+        [
+            (&self.select, position),
+            (&self.new, position + vec2(0.0, 40.0)),
+            (&self.delete, position + vec2(0.0, 80.0)),
+        ]
+    }
+
+    fn create(&mut self) {
+        let info = CharacterInfo::new("", HAIRS[0], FACES[0]);
+        self.creating = Some(Creation {
+            hair: 0,
+            face: 0,
+            avatar: info.avatar(&self.root, self.z_map.clone()),
+        });
+    }
+
+    // moves hair and face on by that many presets
+    fn restyle(&mut self, hair: usize, face: usize) {
+        let Some(creation) = &mut self.creating else {
+            return;
+        };
+        creation.hair = (creation.hair + hair) % HAIRS.len();
+        creation.face = (creation.face + face) % FACES.len();
+        creation.avatar = creation.info("").avatar(&self.root, self.z_map.clone());
+    }
+
+    fn confirm(&mut self) {
+        let Some(creation) = self.creating.take() else {
+            return;
+        };
+        let name = format!("{}{}", self.account, self.characters.len() + 1);
+        self.characters
+            .push((creation.info(&name), creation.avatar));
+        self.pose(self.characters.len() - 1);
+    }
+
+    fn delete(&mut self) {
+        if self.selected < self.characters.len() {
+            self.characters.remove(self.selected);
+            self.selected = 0;
+            self.pose(0);
+        }
+    }
+
+    fn play(&self) -> Transition {
+        match self.characters.get(self.selected) {
+            Some((info, _)) => Transition::Push(Box::new(LoadingScene::new(info.clone()))),
+            None => Transition::None,
+        }
+    }
+
+    fn key(&mut self, scancode: SDL_Scancode::Type) -> Transition {
+        let count = self.characters.len().max(1);
+        match scancode {
+            SDL_Scancode::SDL_SCANCODE_ESCAPE if self.creating.is_some() => {
+                self.creating = None;
+            }
+            SDL_Scancode::SDL_SCANCODE_ESCAPE => return Transition::Pop,
+            SDL_Scancode::SDL_SCANCODE_RETURN if self.creating.is_some() => self.confirm(),
+            SDL_Scancode::SDL_SCANCODE_RETURN => return self.play(),
+            SDL_Scancode::SDL_SCANCODE_LEFT if self.creating.is_some() => {
+                self.restyle(HAIRS.len() - 1, 0)
+            }
+            SDL_Scancode::SDL_SCANCODE_RIGHT if self.creating.is_some() => self.restyle(1, 0),
+            SDL_Scancode::SDL_SCANCODE_UP if self.creating.is_some() => {
+                self.restyle(0, FACES.len() - 1)
+            }
+            SDL_Scancode::SDL_SCANCODE_DOWN if self.creating.is_some() => self.restyle(0, 1),
+            SDL_Scancode::SDL_SCANCODE_LEFT => {
+                self.pose((self.selected + count - 1) % count);
+            }
+            SDL_Scancode::SDL_SCANCODE_RIGHT => {
+                self.pose((self.selected + 1) % count);
+            }
+            SDL_Scancode::SDL_SCANCODE_N if self.creating.is_none() => self.create(),
+            SDL_Scancode::SDL_SCANCODE_DELETE if self.creating.is_none() => self.delete(),
+            _ => {}
+        }
+        Transition::None
+    }
+}
+
+impl Scene for SelectScene {
+    fn event(&mut self, ctx: &mut Context, event: &Event) -> Transition {
+        match event {
+            Event::KeyDown { scancode, .. } => return self.key(*scancode),
+            Event::MouseButtonDown { .. } => {
+                self.pressed = true;
+            }
+            Event::MouseButtonUp { x, y, .. } => {
+                self.pressed = false;
+                let point = vec2(*x, *y);
+                let [select, new, delete] = self.buttons(ctx.size).map(|(button, position)| {
+                    button
+                        .as_ref()
+                        .is_some_and(|button| button.contains(position, point))
+                });
+                if select {
+                    return self.key(SDL_Scancode::SDL_SCANCODE_RETURN);
+                } else if new && self.creating.is_none() {
+                    self.create();
+                } else if delete && self.creating.is_none() {
+                    self.delete();
+                }
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, delta: f32, _alpha: f32) {
+        let renderer = &mut *ctx.renderer;
+        if let Some(creation) = &mut self.creating {
+            let position = vec2(ctx.size.x / 2.0, ctx.size.y * 0.6);
+            creation.avatar.tick(delta);
+            for sprite in &creation.avatar.frame() {
+                renderer.draw(sprite, position);
+            }
+            renderer.draw_text(
+                &format!(
+                    "Hair {}/{}  Face {}/{}",
+                    creation.hair + 1,
+                    HAIRS.len(),
+                    creation.face + 1,
+                    FACES.len()
+                ),
+                position + vec2(-70.0, 20.0),
+            );
+            renderer.draw_text(
+                "Arrows to restyle, Enter to create, Esc to cancel",
+                vec2(20.0, ctx.size.y - 40.0),
+            );
+            return;
+        }
+
+        let spacing = 120.0;
+        let left = ctx.size.x / 2.0 - spacing * (self.characters.len() as f32 - 1.0) / 2.0;
+        for (index, (info, avatar)) in self.characters.iter_mut().enumerate() {
+            let position = vec2(left + spacing * index as f32, ctx.size.y * 0.6);
+            avatar.tick(delta);
+            for sprite in &avatar.frame() {
+                renderer.draw(sprite, position);
+            }
+            renderer.draw_text(&info.name, position + vec2(-30.0, 10.0));
+        }
+        renderer.draw_text(
+            "Enter to play, N to create, Delete to remove, Esc to log out",
+            vec2(20.0, ctx.size.y - 40.0),
+        );
+
+        for (button, position) in self.buttons(ctx.size) {
+            let frame = button
+                .as_ref()
+                .and_then(|button| button.frame(position, ctx.mouse, self.pressed));
+            if let Some(frame) = frame {
+                renderer.draw(frame, position);
+            }
+        }
+    }
+}
@@ -1,154 +1,63 @@
 use ab_glyph::FontVec;
-use glam::{vec2, Vec2, Vec4};
-use image::DynamicImage;
-use sdl_sys::{
-    self, SDL_CreateTexture, SDL_FRect, SDL_FlipMode, SDL_GetRendererFromTexture, SDL_PixelFormat,
-    SDL_RenderTextureRotated, SDL_Renderer, SDL_SetTextureBlendMode, SDL_SetTextureScaleMode,
-    SDL_Texture, SDL_UpdateTexture,
-};
-use std::{borrow::Borrow, collections::HashMap, sync::Arc};
+use glam::{vec2, Vec2};
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use sdl_sys::{SDL_FRect, SDL_FlipMode};
+use std::{collections::HashMap, sync::Arc};
 
+use crate::atlas::Atlas;
+use crate::backend::{Backend, Quad, SoftwareBackend, TextureId};
 use crate::sprite::Sprite;
 
-pub struct Surface {
-    pub surface: *mut sdl_sys::SDL_Surface,
-    pub width: i32,
-    pub height: i32,
-}
-
-impl Surface {
-    pub fn new(width: i32, height: i32) -> Self {
-        unsafe {
-            Self {
-                surface: sdl_sys::SDL_CreateSurface(
-                    width,
-                    height,
-                    sdl_sys::SDL_PixelFormat::SDL_PIXELFORMAT_ABGR8888,
-                ),
-                width,
-                height,
-            }
-        }
-    }
-
-    pub fn blit(&self, dest: &Surface, x: i32, y: i32) {
-        unsafe {
-            sdl_sys::SDL_BlitSurface(
-                self.surface,
-                &sdl_sys::SDL_Rect {
-                    x: 0,
-                    y: 0,
-                    w: self.width,
-                    h: self.height,
-                },
-                dest.surface,
-                &sdl_sys::SDL_Rect {
-                    x,
-                    y,
-                    w: self.width,
-                    h: self.height,
-                },
-            );
-        }
-    }
-}
-
-impl From<Arc<DynamicImage>> for Surface {
-    fn from(value: Arc<DynamicImage>) -> Self {
-        let w = value.width() as i32;
-        let h = value.height() as i32;
-        let bytes = match value.borrow() {
-            DynamicImage::ImageRgba8(data) => data,
-            _ => &value.clone().to_rgba8(),
-        };
-        Self {
-            surface: unsafe {
-                sdl_sys::SDL_CreateSurfaceFrom(
-                    w,
-                    h,
-                    sdl_sys::SDL_PixelFormat::SDL_PIXELFORMAT_ABGR8888,
-                    bytes.as_ptr() as *mut core::ffi::c_void,
-                    w * 4,
-                )
-            },
-            width: value.width() as i32,
-            height: value.height() as i32,
-        }
-    }
-}
-
-impl Drop for Surface {
-    fn drop(&mut self) {
-        unsafe {
-            sdl_sys::SDL_DestroySurface(self.surface);
-        }
-    }
-}
+pub use crate::backend::BlendMode;
 
+#[derive(Clone, Copy)]
 pub struct Texture {
-    pub texture: *mut SDL_Texture,
-    pub renderer: *mut SDL_Renderer,
+    pub id: TextureId,
     pub size: Vec2,
+    // the part of the backend texture holding the image, in pixels
+    pub region: SDL_FRect,
 }
 
 impl Texture {
-    pub fn from_surface(surface: &Surface, renderer: *mut SDL_Renderer) -> Self {
-        let texture = unsafe { sdl_sys::SDL_CreateTextureFromSurface(renderer, surface.surface) };
+    fn whole(id: TextureId, width: u32, height: u32, size: Vec2) -> Self {
         Self {
-            texture,
-            renderer,
-            size: vec2(surface.width as f32, surface.height as f32),
+            id,
+            size,
+            region: SDL_FRect {
+                x: 0.0,
+                y: 0.0,
+                w: width as f32,
+                h: height as f32,
+            },
         }
     }
+}
 
-    pub fn from_image(image: &DynamicImage, renderer: *mut SDL_Renderer) -> Self {
-        let texture = unsafe {
-            SDL_CreateTexture(
-                renderer,
-                SDL_PixelFormat::SDL_PIXELFORMAT_ABGR8888,
-                sdl_sys::SDL_TextureAccess::SDL_TEXTUREACCESS_STATIC,
-                image.width() as i32,
-                image.height() as i32,
-            )
-        };
-        unsafe {
-            let bytes = match image {
-                DynamicImage::ImageRgba8(data) => data,
-                _ => &image.clone().to_rgba8(),
-            };
-            SDL_UpdateTexture(
-                texture,
-                std::ptr::null(),
-                bytes.as_ptr() as *const core::ffi::c_void,
-                image.width() as i32 * 4,
-            );
-            sdl_sys::SDL_SetTextureScaleMode(
-                texture,
-                sdl_sys::SDL_ScaleMode::SDL_SCALEMODE_NEAREST,
-            );
-        }
-        Texture {
-            texture,
-            renderer,
-            size: vec2(image.width() as f32, image.height() as f32),
-        }
-    }
+// consecutive quads of one texture, flushed as a single draw call
+struct Batch {
+    texture: TextureId,
+    blend: BlendMode,
+    quads: Vec<Quad>,
+    bounds: SDL_FRect,
+}
 
-    pub fn ptr(&self) -> *mut SDL_Texture {
-        self.texture
-    }
+fn overlaps(a: &SDL_FRect, b: &SDL_FRect) -> bool {
+    a.x < b.x + b.w && b.x < a.x + a.w && a.y < b.y + b.h && b.y < a.y + a.h
 }
 
-impl Drop for Texture {
-    fn drop(&mut self) {
-        unsafe {
-            sdl_sys::SDL_DestroyTexture(self.texture);
-        }
+fn union(a: &SDL_FRect, b: &SDL_FRect) -> SDL_FRect {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    SDL_FRect {
+        x,
+        y,
+        w: (a.x + a.w).max(b.x + b.w) - x,
+        h: (a.y + a.h).max(b.y + b.h) - y,
     }
 }
 
 pub struct NineGridTexture {
-    pub texture: Texture,
+    pub image: Arc<DynamicImage>,
     pub left_width: i32,
     pub middle_width: i32,
     pub right_width: i32,
@@ -157,44 +66,65 @@ pub struct NineGridTexture {
     pub bottom_height: i32,
 }
 
+type NineGridParts<'a> = (
+    &'a DynamicImage,
+    &'a DynamicImage,
+    &'a DynamicImage,
+    &'a DynamicImage,
+    &'a DynamicImage,
+    &'a DynamicImage,
+    &'a DynamicImage,
+    &'a DynamicImage,
+);
+
 impl NineGridTexture {
-    pub fn new(
-        (lt, t, rt, lm, rm, lb, b, rb): (
-            &Surface,
-            &Surface,
-            &Surface,
-            &Surface,
-            &Surface,
-            &Surface,
-            &Surface,
-            &Surface,
-        ),
-        renderer: *mut SDL_Renderer,
+    pub fn new(parts: NineGridParts) -> Self {
+        Self::compose(parts, None)
+    }
+
+    /// Like [`NineGridTexture::new`], but with a center piece for frames that
+    /// aren't covered by content, e.g. chat balloons.
+    pub fn new_filled(parts: NineGridParts, center: &DynamicImage) -> Self {
+        Self::compose(parts, Some(center))
+    }
+
+    fn compose(
+        (lt, t, rt, lm, rm, lb, b, rb): NineGridParts,
+        center: Option<&DynamicImage>,
     ) -> Self {
-        let left_width = lt.width.min(lb.width).min(lm.width);
-        let middle_width = t.width.min(b.width);
-        let right_width = rt.width.min(rb.width).min(rm.width);
+        let width = |image: &DynamicImage| image.width() as i32;
+        let height = |image: &DynamicImage| image.height() as i32;
 
-        let top_height = lt.height.min(t.height).min(rt.height);
-        let middle_height = lm.height.min(rm.height);
-        let bottom_height = lb.height.min(b.height).min(rb.height);
+        let left_width = width(lt).min(width(lb)).min(width(lm));
+        let middle_width = width(t).min(width(b));
+        let right_width = width(rt).min(width(rb)).min(width(rm));
 
-        let dest = Surface::new(
-            left_width + middle_width + right_width,
-            top_height + middle_height + bottom_height,
+        let top_height = height(lt).min(height(t)).min(height(rt));
+        let middle_height = height(lm).min(height(rm));
+        let bottom_height = height(lb).min(height(b)).min(height(rb));
+
+        let mut dest = RgbaImage::new(
+            (left_width + middle_width + right_width) as u32,
+            (top_height + middle_height + bottom_height) as u32,
         );
 
-        lt.blit(&dest, 0, 0);
-        t.blit(&dest, left_width, 0);
-        rt.blit(&dest, left_width + middle_width, 0);
-        lm.blit(&dest, 0, top_height);
-        rm.blit(&dest, left_width + middle_width, top_height);
-        lb.blit(&dest, 0, top_height + middle_height);
-        b.blit(&dest, left_width, top_height + middle_height);
-        rb.blit(&dest, left_width + middle_width, top_height + middle_height);
+        let mut blit = |image: &DynamicImage, x: i32, y: i32| {
+            imageops::overlay(&mut dest, image, x as i64, y as i64);
+        };
+        if let Some(center) = center {
+            blit(center, left_width, top_height);
+        }
+        blit(lt, 0, 0);
+        blit(t, left_width, 0);
+        blit(rt, left_width + middle_width, 0);
+        blit(lm, 0, top_height);
+        blit(rm, left_width + middle_width, top_height);
+        blit(lb, 0, top_height + middle_height);
+        blit(b, left_width, top_height + middle_height);
+        blit(rb, left_width + middle_width, top_height + middle_height);
 
         Self {
-            texture: Texture::from_surface(&dest, renderer),
+            image: Arc::new(DynamicImage::ImageRgba8(dest)),
             left_width,
             middle_width,
             right_width,
@@ -204,31 +134,6 @@ impl NineGridTexture {
         }
     }
 
-    pub fn ptr(&self) -> *mut SDL_Texture {
-        self.texture.ptr()
-    }
-
-    pub fn draw(&self, offset: Vec2, size: Vec2) {
-        unsafe {
-            sdl_sys::SDL_RenderTexture9Grid(
-                self.texture.renderer,
-                self.ptr(),
-                std::ptr::null(),
-                self.left_width as f32,
-                self.right_width as f32,
-                self.top_height as f32,
-                self.bottom_height as f32,
-                1.0,
-                &SDL_FRect {
-                    x: offset.x,
-                    y: offset.y,
-                    w: size.x,
-                    h: size.y,
-                },
-            );
-        }
-    }
-
     pub fn border_size(&self) -> Vec2 {
         vec2(
             (self.left_width + self.right_width) as f32,
@@ -237,19 +142,69 @@ impl NineGridTexture {
     }
 }
 
-pub struct Renderer {
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextStyle {
+    pub size: u32,
+    pub color: [u8; 4],
+    pub max_width: u32,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            size: 12,
+            color: [0, 0, 0, 255],
+            max_width: 10000,
+        }
+    }
+}
+
+/// How a sprite is drawn, on top of where.
+#[derive(Debug, Clone, Copy)]
+pub struct DrawParams {
+    pub alpha: i32,
+    pub flip: bool,
+    pub tint: [u8; 3],
+    pub blend: BlendMode,
+    /// Clockwise, in degrees, around the sprite's origin.
+    pub rotation: f32,
+    /// Around the sprite's origin as well.
+    pub scale: Vec2,
+}
+
+impl Default for DrawParams {
+    fn default() -> Self {
+        Self {
+            alpha: 255,
+            flip: false,
+            tint: [255, 255, 255],
+            blend: BlendMode::Blend,
+            rotation: 0.0,
+            scale: Vec2::ONE,
+        }
+    }
+}
+
+pub struct Renderer<'r> {
     dpr: f32,
     font: FontVec,
-    renderer: *mut SDL_Renderer,
-    textures: HashMap<*const DynamicImage, Arc<Texture>>,
-    text_textures: HashMap<String, Arc<Texture>>,
+    backend: Box<dyn Backend + 'r>,
+    textures: HashMap<*const DynamicImage, Texture>,
+    // the loaded atlas' pages and the images packed into them
+    atlas_pages: Vec<TextureId>,
+    atlas_images: Vec<*const DynamicImage>,
+    // with when each was last drawn, the least recent evicted past
+    // `TEXT_TEXTURES`
+    text_textures: HashMap<(String, TextStyle), (Texture, u64)>,
+    text_clock: u64,
+    batches: Vec<Batch>,
+    draw_calls: usize,
 }
 
-unsafe impl Send for Renderer {}
-unsafe impl Sync for Renderer {}
+impl<'r> Renderer<'r> {
+    const TEXT_TEXTURES: usize = 512;
 
-impl Renderer {
-    pub fn new(dpr: f32, renderer: *mut SDL_Renderer) -> Self {
+    pub fn new(dpr: f32, backend: Box<dyn Backend + 'r>) -> Self {
         let font =
             // FontRef::try_from_slice(include_bytes!("../../.././Data/WenQuanYiMicroHei.ttf"))?;
         // FontVec::try_from_vec(include_bytes!("../../.././Data/SourceHanSerifSC-Regular.otf").to_vec()).unwrap();
@@ -258,141 +213,367 @@ impl Renderer {
         Self {
             dpr,
             font,
-            renderer,
+            backend,
             textures: Default::default(),
+            atlas_pages: vec![],
+            atlas_images: vec![],
             text_textures: Default::default(),
+            text_clock: 0,
+            batches: vec![],
+            draw_calls: 0,
+        }
+    }
+
+    /// A renderer without a window, drawing `width` x `height` logical units
+    /// into an image with `dpr` pixels per unit.
+    pub fn offscreen(width: u32, height: u32, dpr: f32) -> Self {
+        Self::new(dpr, Box::new(SoftwareBackend::new(width, height, dpr)))
+    }
+
+    /// Changes the pixels per unit, e.g. after the window moved to a display
+    /// of another density. Text is rasterized again at the new density.
+    pub fn set_dpr(&mut self, dpr: f32) {
+        if self.dpr != dpr {
+            self.dpr = dpr;
+            self.clear_text_textures();
+        }
+    }
+
+    /// Starts a frame, resetting the draw call count.
+    pub fn clear(&mut self, color: [u8; 4]) {
+        self.batches.clear();
+        self.draw_calls = 1;
+        self.backend.clear(color);
+    }
+
+    pub fn present(&mut self) {
+        self.flush();
+        self.backend.present();
+    }
+
+    /// The frame drawn so far, in physical pixels.
+    pub fn snapshot(&mut self) -> RgbaImage {
+        self.flush();
+        self.backend.snapshot()
+    }
+
+    /// Backend draw calls issued since the last [`Renderer::clear`].
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls
+    }
+
+    /// Uploads the atlas pages and draws the packed images from them from
+    /// now on, freeing the atlas loaded before.
+    pub fn load_atlas(&mut self, atlas: &Atlas) {
+        self.unload_atlas();
+        let pages: Vec<TextureId> = atlas
+            .pages
+            .iter()
+            .map(|page| self.backend.create_texture(page))
+            .collect();
+        self.atlas_images = atlas
+            .regions
+            .iter()
+            .map(|(image, _)| Arc::as_ptr(image))
+            .collect();
+        for (image, region) in &atlas.regions {
+            let previous = self.textures.insert(
+                Arc::as_ptr(image),
+                Texture {
+                    id: pages[region.page],
+                    size: vec2(region.width as f32, region.height as f32),
+                    region: SDL_FRect {
+                        x: region.x as f32,
+                        y: region.y as f32,
+                        w: region.width as f32,
+                        h: region.height as f32,
+                    },
+                },
+            );
+            // uploaded on its own before it was packed
+            if let Some(previous) = previous.filter(|texture| !pages.contains(&texture.id)) {
+                self.backend.destroy_texture(previous.id);
+            }
+        }
+        self.atlas_pages = pages;
+    }
+
+    fn unload_atlas(&mut self) {
+        // queued quads may still use the pages
+        self.flush();
+        for image in self.atlas_images.drain(..) {
+            self.textures.remove(&image);
+        }
+        for page in self.atlas_pages.drain(..) {
+            self.backend.destroy_texture(page);
+        }
+    }
+
+    // Queues a quad, joining an earlier batch of the same texture when
+    // nothing queued after that batch overlaps it, so the result looks the
+    // same as drawing in order.
+    fn push(&mut self, texture: TextureId, quad: Quad) {
+        const LOOKBACK: usize = 32;
+        let bounds = quad.bounds();
+        for batch in self.batches.iter_mut().rev().take(LOOKBACK) {
+            if batch.texture == texture && batch.blend == quad.blend {
+                batch.bounds = union(&batch.bounds, &bounds);
+                batch.quads.push(quad);
+                return;
+            }
+            if overlaps(&batch.bounds, &bounds) {
+                break;
+            }
+        }
+        self.batches.push(Batch {
+            texture,
+            blend: quad.blend,
+            quads: vec![quad],
+            bounds,
+        });
+    }
+
+    /// Submits the queued quads, one draw call per batch.
+    pub fn flush(&mut self) {
+        for batch in self.batches.drain(..) {
+            self.backend.copy_batch(batch.texture, &batch.quads);
+            self.draw_calls += 1;
         }
     }
 
-    pub fn texture(&mut self, image: &Arc<DynamicImage>) -> Arc<Texture> {
-        self.textures
-            .entry(Arc::as_ptr(image))
-            .or_insert_with(|| Texture::from_image(image, self.renderer).into())
-            .clone()
+    pub fn texture(&mut self, image: &Arc<DynamicImage>) -> Texture {
+        let backend = &mut self.backend;
+        *self.textures.entry(Arc::as_ptr(image)).or_insert_with(|| {
+            let id = match image.as_ref() {
+                DynamicImage::ImageRgba8(data) => backend.create_texture(data),
+                _ => backend.create_texture(&image.to_rgba8()),
+            };
+            Texture::whole(
+                id,
+                image.width(),
+                image.height(),
+                vec2(image.width() as f32, image.height() as f32),
+            )
+        })
     }
 
     pub fn draw(&mut self, sprite: &Sprite, position: Vec2) {
         self.draw_flip(sprite, position, false);
     }
 
+    /// `text` rasterized, cached for the most recently drawn strings.
+    pub fn text(&mut self, text: &str, style: TextStyle) -> Texture {
+        self.text_clock += 1;
+        let key = (text.to_string(), style);
+        if let Some((texture, used)) = self.text_textures.get_mut(&key) {
+            *used = self.text_clock;
+            return *texture;
+        }
+        if self.text_textures.len() >= Self::TEXT_TEXTURES {
+            self.evict_text_texture();
+        }
+        let image = crate::layout::draw_paragraph(
+            &self.font,
+            style.size as f32 * self.dpr,
+            style.max_width as f32 * self.dpr,
+            Rgba(style.color),
+            text,
+        );
+        let texture = Texture::whole(
+            self.backend.create_texture(&image),
+            image.width(),
+            image.height(),
+            vec2(
+                image.width() as f32 / self.dpr,
+                image.height() as f32 / self.dpr,
+            ),
+        );
+        self.text_textures.insert(key, (texture, self.text_clock));
+        texture
+    }
+
+    fn evict_text_texture(&mut self) {
+        let Some(key) = self
+            .text_textures
+            .iter()
+            .min_by_key(|(_, (_, used))| *used)
+            .map(|(key, _)| key.clone())
+        else {
+            return;
+        };
+        // queued quads may still use it
+        self.flush();
+        if let Some((texture, _)) = self.text_textures.remove(&key) {
+            self.backend.destroy_texture(texture.id);
+        }
+    }
+
+    fn clear_text_textures(&mut self) {
+        self.flush();
+        for (_, (texture, _)) in self.text_textures.drain() {
+            self.backend.destroy_texture(texture.id);
+        }
+    }
+
+    /// Where the caret goes before each of `text`'s characters and after
+    /// the last, from the left edge, in logical units.
+    pub fn caret_offsets(&self, text: &str, style: TextStyle) -> Vec<f32> {
+        crate::layout::caret_offsets(&self.font, style.size as f32 * self.dpr, text)
+            .into_iter()
+            .map(|x| x / self.dpr)
+            .collect()
+    }
+
+    /// The size `text` is drawn at, in logical units.
+    pub fn measure_text(&self, text: &str, style: TextStyle) -> Vec2 {
+        crate::layout::measure_paragraph(
+            &self.font,
+            style.size as f32 * self.dpr,
+            style.max_width as f32 * self.dpr,
+            text,
+        ) / self.dpr
+    }
+
     pub fn draw_text(&mut self, text: &str, position: Vec2) {
         if text.is_empty() {
             return;
         }
-        let texture = self
-            .text_textures
-            .entry(text.to_string())
-            .or_insert_with(|| unsafe {
-                let image = crate::layout::draw_image(&self.font, 14.0 * self.dpr, text);
-
-                let text_texture = SDL_CreateTexture(
-                    self.renderer,
-                    SDL_PixelFormat::SDL_PIXELFORMAT_ABGR8888,
-                    sdl_sys::SDL_TextureAccess::SDL_TEXTUREACCESS_STATIC,
-                    image.width() as i32,
-                    image.height() as i32,
-                );
-                SDL_UpdateTexture(
-                    text_texture,
-                    std::ptr::null(),
-                    image.as_ptr() as *const core::ffi::c_void,
-                    image.width() as i32 * 4,
-                );
-                SDL_SetTextureScaleMode(
-                    text_texture,
-                    sdl_sys::SDL_ScaleMode::SDL_SCALEMODE_NEAREST,
-                );
-                SDL_SetTextureBlendMode(text_texture, sdl_sys::SDL_BLENDMODE_BLEND);
+        let texture = self.text(
+            text,
+            TextStyle {
+                size: 14,
+                ..Default::default()
+            },
+        );
+        self.fill_rect(
+            &SDL_FRect {
+                x: position.x - 4.0,
+                y: position.y - 2.0,
+                w: texture.size.x + 8.0,
+                h: texture.size.y + 4.0,
+            },
+            [255, 255, 255, 255],
+        );
+        self.render_texture(&texture, position, Vec2::ZERO, None, DrawParams::default());
+    }
 
-                Texture {
-                    texture: text_texture,
-                    renderer: self.renderer,
-                    size: vec2(
-                        image.width() as f32 / self.dpr,
-                        image.height() as f32 / self.dpr,
-                    ),
-                }
-                .into()
-            })
-            .clone();
-        unsafe {
-            sdl_sys::SDL_SetRenderDrawColor(self.renderer, 255, 255, 255, 255);
-            sdl_sys::SDL_RenderFillRect(
-                self.renderer,
-                &SDL_FRect {
-                    x: position.x - 4.0,
-                    y: position.y - 2.0,
-                    w: texture.size.x + 8.0,
-                    h: texture.size.y + 4.0,
-                },
-            );
-        }
-        self.render_texture(
-            &texture,
+    pub fn draw_alpha(&mut self, sprite: &Sprite, position: Vec2, alpha: i32) {
+        self.draw_with(
+            sprite,
             position,
-            Vec2::ZERO,
-            255,
-            None,
-            SDL_FlipMode::SDL_FLIP_NONE,
+            DrawParams {
+                alpha,
+                ..Default::default()
+            },
         );
     }
 
     pub fn draw_flip(&mut self, sprite: &Sprite, position: Vec2, flip: bool) {
-        let texture = self.texture(&sprite.image);
-        self.render_texture(
-            &texture,
+        self.draw_with(
+            sprite,
             position,
-            sprite.origin,
-            sprite.alpha,
-            None,
-            if flip {
-                SDL_FlipMode::SDL_FLIP_HORIZONTAL
-            } else {
-                SDL_FlipMode::SDL_FLIP_NONE
+            DrawParams {
+                alpha: sprite.alpha,
+                flip,
+                ..Default::default()
             },
-        )
+        );
     }
 
-    pub fn render_rect(&self, rect: &SDL_FRect) {
-        unsafe {
-            sdl_sys::SDL_RenderRect(self.renderer, rect);
-        }
+    pub fn draw_with(&mut self, sprite: &Sprite, position: Vec2, params: DrawParams) {
+        self.draw_image(&sprite.image, sprite.origin, position, params);
+    }
+
+    pub fn draw_image(
+        &mut self,
+        image: &Arc<DynamicImage>,
+        origin: Vec2,
+        position: Vec2,
+        params: DrawParams,
+    ) {
+        let texture = self.texture(image);
+        self.render_texture(&texture, position, origin, None, params)
+    }
+
+    pub fn draw_nine_grid(&mut self, grid: &NineGridTexture, offset: Vec2, size: Vec2, alpha: i32) {
+        let texture = self.texture(&grid.image);
+        self.flush();
+        self.draw_calls += 1;
+        self.backend.copy_9grid(
+            texture.id,
+            &texture.region,
+            [
+                grid.left_width as f32,
+                grid.right_width as f32,
+                grid.top_height as f32,
+                grid.bottom_height as f32,
+            ],
+            &SDL_FRect {
+                x: offset.x,
+                y: offset.y,
+                w: size.x,
+                h: size.y,
+            },
+            alpha.clamp(0, 255) as u8,
+        );
+    }
+
+    pub fn fill_rect(&mut self, rect: &SDL_FRect, color: [u8; 4]) {
+        self.flush();
+        self.draw_calls += 1;
+        self.backend.fill_rect(rect, color);
+    }
+
+    pub fn render_rect(&mut self, rect: &SDL_FRect, color: [u8; 4]) {
+        self.flush();
+        self.draw_calls += 1;
+        self.backend.draw_rect(rect, color);
     }
 
+    pub fn render_line(&mut self, from: Vec2, to: Vec2, color: [u8; 4]) {
+        self.flush();
+        self.draw_calls += 1;
+        self.backend.draw_line(from, to, color);
+    }
+
+    /// Draws `texture` with its `origin` at `position`, `size` overriding the
+    /// texture's own before `params.scale` applies.
     pub fn render_texture(
-        &self,
+        &mut self,
         texture: &Texture,
         position: Vec2,
         origin: Vec2,
-        alpha: i32,
         size: Option<Vec2>,
-        flip: SDL_FlipMode::Type,
+        params: DrawParams,
     ) {
-        let size = size.unwrap_or(texture.size);
-        unsafe {
-            sdl_sys::SDL_SetTextureAlphaMod(texture.texture, alpha as u8);
-            SDL_RenderTextureRotated(
-                self.renderer,
-                texture.texture,
-                std::ptr::null(),
-                &SDL_FRect {
-                    x: if flip == SDL_FlipMode::SDL_FLIP_HORIZONTAL {
-                        position.x - (texture.size.x - origin.x)
-                    } else {
-                        position.x - origin.x
-                    },
-                    y: if flip == SDL_FlipMode::SDL_FLIP_VERTICAL {
-                        position.y - (texture.size.y - origin.y)
-                    } else {
-                        position.y - origin.y
-                    },
+        let size = size.unwrap_or(texture.size) * params.scale;
+        // measured from the left edge of what ends up on screen
+        let origin = if params.flip {
+            vec2(texture.size.x - origin.x, origin.y)
+        } else {
+            origin
+        } * params.scale;
+        let [r, g, b] = params.tint;
+        self.push(
+            texture.id,
+            Quad {
+                src: texture.region,
+                dst: SDL_FRect {
+                    x: position.x - origin.x,
+                    y: position.y - origin.y,
                     w: size.x,
                     h: size.y,
                 },
-                0.0,
-                std::ptr::null(),
-                flip,
-            );
-        }
+                color: [r, g, b, params.alpha.clamp(0, 255) as u8],
+                flip: if params.flip {
+                    SDL_FlipMode::SDL_FLIP_HORIZONTAL
+                } else {
+                    SDL_FlipMode::SDL_FLIP_NONE
+                },
+                angle: params.rotation,
+                center: origin,
+                blend: params.blend,
+            },
+        );
     }
 }
@@ -2,9 +2,10 @@ use glam::{vec2, Vec2};
 use image::DynamicImage;
 use std::sync::Arc;
 
-use crate::timer::Timer;
+use crate::timer::{Playback, Timer};
 use crate::wz::Node;
 
+#[derive(Clone)]
 pub struct Sprite {
     pub path: String,
     pub image: Arc<DynamicImage>,
@@ -24,9 +25,12 @@ pub struct SpriteAnimation {
 
 impl From<Node> for SpriteAnimation {
     fn from(node: Node) -> Self {
-        let frames: Vec<Sprite> = node.into();
+        let frames: Vec<Sprite> = node.clone().into();
         Self {
-            timer: Timer::new(frames.iter().map(|frame| frame.delay as f32).collect()),
+            timer: Timer::with_playback(
+                frames.iter().map(|frame| frame.delay as f32).collect(),
+                playback(&node),
+            ),
             frames,
         }
     }
@@ -36,23 +40,50 @@ impl SpriteAnimation {
     pub fn tick(&mut self, delta: f32) -> &Sprite {
         self.timer.tick(delta);
         let sprite = &mut self.frames[self.timer.index];
-        let p = self.timer.progress();
-        sprite.alpha = ((1.0 - p) * sprite.a0 as f32 + p * sprite.a1 as f32) as i32;
+        sprite.alpha = sprite.alpha_at(self.timer.progress());
         sprite
     }
 }
 
+// animation flags are set either on the animation or on its first frame
+pub(crate) fn animation_property(node: &Node, name: &str) -> Option<Node> {
+    node.try_get(name)
+        .or_else(|| node.try_get("0")?.try_get(name))
+}
+
+/// How an animation node wants its frames played: `zigzag` runs them back
+/// and forth and `repeat: 0` stops on the last one.
+pub fn playback(node: &Node) -> Playback {
+    let flag = |name| animation_property(node, name).map(i32::from);
+    if flag("zigzag").unwrap_or(0) != 0 {
+        Playback::PingPong
+    } else if flag("repeat") == Some(0) {
+        Playback::Once
+    } else {
+        Playback::Loop
+    }
+}
+
+impl Sprite {
+    /// Alpha `progress` of the way through the frame, fading from `a0` to `a1`.
+    pub fn alpha_at(&self, progress: f32) -> i32 {
+        ((1.0 - progress) * self.a0 as f32 + progress * self.a1 as f32) as i32
+    }
+}
+
 impl From<Node> for Sprite {
     fn from(node: Node) -> Self {
         let origin: Vec2 = node.get("origin").into();
         let image: Arc<DynamicImage> = node.clone().into();
+        // frames without a fade stay opaque
+        let a0 = node.try_get("a0").map(Into::into).unwrap_or(255);
         Self {
             path: node.path(),
             origin,
             z: node.try_get("z").map(Into::into).unwrap_or(0),
             delay: node.try_get("delay").map(Into::into).unwrap_or(100),
-            a0: node.try_get("a0").map(Into::into).unwrap_or(0),
-            a1: node.try_get("a1").map(Into::into).unwrap_or(0),
+            a0,
+            a1: node.try_get("a1").map(Into::into).unwrap_or(a0),
             alpha: 255,
             size: vec2(image.width() as f32, image.height() as f32),
             image,
@@ -1,32 +1,178 @@
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    #[default]
+    Loop,
+    /// Stops on the last frame.
+    Once,
+    /// Runs back and forth, `zigzag` in the data.
+    PingPong,
+    /// Loops from the last frame to the first.
+    Reverse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerEvent {
+    /// A frame was entered.
+    Frame(usize),
+    /// The animation got back to its first frame, the last in reverse.
+    Looped,
+    /// A [`Playback::Once`] animation reached its end.
+    Finished,
+}
+
+/// Animation clock stepping through frames of the given intervals in ms.
+#[derive(Debug)]
 pub struct Timer {
     elapsed: f32,
     intervals: Vec<f32>,
     total: f32,
     pub index: usize,
+    pub playback: Playback,
+    /// Multiplies the time passed to [`Timer::tick`].
+    pub speed: f32,
+    backwards: bool,
+    finished: bool,
+    events: Vec<TimerEvent>,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new(vec![])
+    }
 }
 
 impl Timer {
     pub fn new(intervals: Vec<f32>) -> Self {
-        Self {
+        Self::with_playback(intervals, Playback::Loop)
+    }
+
+    pub fn with_playback(intervals: Vec<f32>, playback: Playback) -> Self {
+        let mut timer = Self {
             total: intervals.iter().sum(),
             elapsed: 0.0,
-            intervals,
             index: 0,
+            intervals,
+            playback,
+            speed: 1.0,
+            backwards: false,
+            finished: false,
+            events: vec![],
+        };
+        timer.reset();
+        timer
+    }
+
+    /// Whether a [`Playback::Once`] timer reached its last frame.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// What happened during the last [`Timer::tick`].
+    pub fn events(&self) -> &[TimerEvent] {
+        &self.events
+    }
+
+    /// Back to the first frame, the last one when playing in reverse.
+    pub fn reset(&mut self) {
+        self.index = match self.playback {
+            Playback::Reverse => self.intervals.len().saturating_sub(1),
+            _ => 0,
+        };
+        self.elapsed = 0.0;
+        self.backwards = false;
+        self.finished = false;
+        self.events.clear();
+    }
+
+    /// Jumps to `time` ms after the start, ignoring `speed`.
+    pub fn seek(&mut self, time: f32) {
+        self.reset();
+        self.advance(time);
+        self.events.clear();
+    }
+
+    /// The length of one run through all frames, in ms.
+    pub fn duration(&self) -> f32 {
+        self.total
+    }
+
+    // skipping whole loops and then wrapping again is still one loop as far
+    // as events go
+    fn push_looped(&mut self) {
+        if !self.events.contains(&TimerEvent::Looped) {
+            self.events.push(TimerEvent::Looped);
         }
     }
 
-    pub fn tick(&mut self, delta: f32) -> bool {
-        if self.intervals.is_empty() || self.total == 0.0 {
-            return false;
+    fn next(&mut self) -> Option<usize> {
+        let last = self.intervals.len() - 1;
+        match self.playback {
+            Playback::Loop => Some(if self.index == last {
+                0
+            } else {
+                self.index + 1
+            }),
+            Playback::Reverse => Some(if self.index == 0 {
+                last
+            } else {
+                self.index - 1
+            }),
+            Playback::Once => (self.index < last).then_some(self.index + 1),
+            Playback::PingPong if last == 0 => Some(0),
+            Playback::PingPong => {
+                if self.index == last {
+                    self.backwards = true;
+                } else if self.index == 0 {
+                    self.backwards = false;
+                }
+                Some(if self.backwards {
+                    self.index - 1
+                } else {
+                    self.index + 1
+                })
+            }
+        }
+    }
+
+    fn advance(&mut self, delta: f32) {
+        if self.intervals.is_empty() || self.total == 0.0 || self.finished {
+            return;
         }
-        let prev = self.index;
         self.elapsed += delta;
-        self.elapsed %= self.total;
+        // whole loops in one step are skipped rather than walked
+        if matches!(self.playback, Playback::Loop | Playback::Reverse) && self.elapsed >= self.total
+        {
+            self.elapsed %= self.total;
+            self.push_looped();
+        }
         while self.elapsed >= self.intervals[self.index] {
+            let Some(next) = self.next() else {
+                self.finished = true;
+                self.elapsed = self.intervals[self.index];
+                self.events.push(TimerEvent::Finished);
+                break;
+            };
             self.elapsed -= self.intervals[self.index];
-            self.index = (self.index + 1) % self.intervals.len();
+            let looped = match self.playback {
+                Playback::Loop => next < self.index,
+                Playback::Reverse => next > self.index,
+                Playback::PingPong => next == 0,
+                Playback::Once => false,
+            };
+            self.index = next;
+            if looped {
+                self.push_looped();
+            }
+            self.events.push(TimerEvent::Frame(next));
         }
+    }
+
+    /// Advances by `delta` ms scaled by `speed`, returning whether the frame
+    /// changed.
+    pub fn tick(&mut self, delta: f32) -> bool {
+        self.events.clear();
+        let prev = self.index;
+        self.advance(delta * self.speed.max(0.0));
         self.index != prev
     }
 
@@ -0,0 +1,103 @@
+use glam::Vec2;
+
+use super::{Rect, UiEvent, Widget};
+use crate::sdl::Renderer;
+use crate::sprite::{Sprite, SpriteAnimation};
+use crate::wz::Node;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonState {
+    #[default]
+    Normal,
+    MouseOver,
+    Pressed,
+    Disabled,
+}
+
+pub struct Button {
+    pub disabled: SpriteAnimation,
+    pub mouse_over: SpriteAnimation,
+    pub normal: SpriteAnimation,
+    pub pressed: SpriteAnimation,
+    pub state: ButtonState,
+}
+
+impl From<Node> for Button {
+    fn from(node: Node) -> Self {
+        Self {
+            disabled: node.get("disabled").into(),
+            mouse_over: node.get("mouseOver").into(),
+            normal: node.get("normal").into(),
+            pressed: node.get("pressed").into(),
+            state: ButtonState::Normal,
+        }
+    }
+}
+
+impl Button {
+    /// The size of the normal frame, which the others share.
+    pub fn size(&self) -> Vec2 {
+        self.normal
+            .frames
+            .first()
+            .map_or(Vec2::ZERO, |sprite| sprite.size)
+    }
+
+    /// Whether `point` is on the button drawn at `position`.
+    pub fn contains(&self, position: Vec2, point: Vec2) -> bool {
+        let Some(sprite) = self.normal.frames.first() else {
+            return false;
+        };
+        let lt = position - sprite.origin;
+        point.cmpge(lt).all() && point.cmplt(lt + sprite.size).all()
+    }
+
+    /// The frame for the pointer at `mouse`, falling back to the normal one.
+    pub fn frame(&self, position: Vec2, mouse: Vec2, pressed: bool) -> Option<&Sprite> {
+        let state = if !self.contains(position, mouse) {
+            ButtonState::Normal
+        } else if pressed {
+            ButtonState::Pressed
+        } else {
+            ButtonState::MouseOver
+        };
+        self.frame_for(state)
+    }
+
+    pub fn frame_for(&self, state: ButtonState) -> Option<&Sprite> {
+        let animation = match state {
+            ButtonState::Normal => &self.normal,
+            ButtonState::MouseOver => &self.mouse_over,
+            ButtonState::Pressed => &self.pressed,
+            ButtonState::Disabled => &self.disabled,
+        };
+        animation.frames.first().or(self.normal.frames.first())
+    }
+}
+
+impl Widget for Button {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        if let Some(sprite) = self.frame_for(self.state) {
+            renderer.draw(sprite, rect.position + sprite.origin);
+        }
+    }
+
+    fn measure(&self, _renderer: &Renderer) -> Vec2 {
+        self.size()
+    }
+
+    fn event(&mut self, event: &UiEvent) {
+        self.state = match (*event, self.state) {
+            (UiEvent::Enabled(false), _) => ButtonState::Disabled,
+            (UiEvent::Enabled(true), _) => ButtonState::Normal,
+            (_, ButtonState::Disabled) => return,
+            (UiEvent::Enter { pressed: true }, _) | (UiEvent::Press(_), _) => ButtonState::Pressed,
+            (UiEvent::Enter { pressed: false }, _) => ButtonState::MouseOver,
+            (UiEvent::Release { inside: true, .. }, _) => ButtonState::MouseOver,
+            (UiEvent::Leave, _) | (UiEvent::Release { inside: false, .. }, _) => {
+                ButtonState::Normal
+            }
+            _ => return,
+        };
+    }
+}
@@ -0,0 +1,170 @@
+use glam::{vec2, Vec2};
+use std::collections::VecDeque;
+
+use super::{
+    Direction, Edges, Fill, FlexItem, FlexView, Rect, TextField, Ui, UiEvent, Widget, WidgetId,
+};
+use crate::sdl::{DrawParams, Renderer, TextStyle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatKind {
+    General,
+    Whisper,
+    Party,
+    System,
+}
+
+impl ChatKind {
+    pub fn color(self) -> [u8; 4] {
+        match self {
+            ChatKind::General => [255, 255, 255, 255],
+            ChatKind::Whisper => [120, 255, 120, 255],
+            ChatKind::Party => [255, 150, 210, 255],
+            ChatKind::System => [255, 220, 90, 255],
+        }
+    }
+}
+
+pub struct ChatMessage {
+    pub kind: ChatKind,
+    pub text: String,
+}
+
+/// The latest messages, newest at the bottom. The wheel scrolls back
+/// through older ones.
+pub struct ChatLog {
+    messages: VecDeque<ChatMessage>,
+    // how many of the newest messages are scrolled past
+    scroll: usize,
+    style: TextStyle,
+}
+
+impl ChatLog {
+    const CAPACITY: usize = 200;
+    const PADDING: f32 = 2.0;
+
+    pub fn new(style: TextStyle) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            scroll: 0,
+            style,
+        }
+    }
+
+    pub fn push(&mut self, kind: ChatKind, text: impl Into<String>) {
+        if self.messages.len() == Self::CAPACITY {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(ChatMessage {
+            kind,
+            text: text.into(),
+        });
+        // what's being read back stays put
+        if self.scroll > 0 {
+            self.scroll = (self.scroll + 1).min(self.messages.len() - 1);
+        }
+    }
+}
+
+impl Widget for ChatLog {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        let style = TextStyle {
+            max_width: (rect.size.x - Self::PADDING * 2.0).max(1.0) as u32,
+            ..self.style
+        };
+        let mut y = rect.position.y + rect.size.y - Self::PADDING;
+        for message in self.messages.iter().rev().skip(self.scroll) {
+            let texture = renderer.text(
+                &message.text,
+                TextStyle {
+                    color: message.kind.color(),
+                    ..style
+                },
+            );
+            y -= texture.size.y;
+            if y < rect.position.y {
+                break;
+            }
+            renderer.render_texture(
+                &texture,
+                vec2(rect.position.x + Self::PADDING, y),
+                Vec2::ZERO,
+                None,
+                DrawParams::default(),
+            );
+        }
+    }
+
+    fn event(&mut self, event: &UiEvent) {
+        if let UiEvent::Scroll(y) = *event {
+            self.scroll = if y > 0.0 {
+                (self.scroll + 1).min(self.messages.len().saturating_sub(1))
+            } else {
+                self.scroll.saturating_sub(1)
+            };
+        }
+    }
+}
+
+/// The chat box: the log over a line to type in.
+pub struct Chat {
+    pub frame: WidgetId,
+    pub log: WidgetId,
+    pub input: WidgetId,
+}
+
+impl Chat {
+    const SIZE: Vec2 = Vec2::new(400.0, 130.0);
+
+    pub fn new(ui: &mut Ui) -> Self {
+        let style = TextStyle {
+            color: [255, 255, 255, 255],
+            ..Default::default()
+        };
+        let frame = ui.add(
+            None,
+            Rect::default(),
+            Fill {
+                color: [0, 0, 0, 128],
+            },
+        );
+        ui.set_layout(
+            frame,
+            FlexView {
+                direction: Direction::Column,
+                gap: 2.0,
+                padding: Edges::all(4.0),
+                ..Default::default()
+            },
+        );
+        let log = ui.add(Some(frame), Rect::default(), ChatLog::new(style));
+        ui.set_flex(log, FlexItem::grow(1.0));
+
+        // the input gets a darker strip of its own
+        let strip = ui.add(
+            Some(frame),
+            Rect::default(),
+            Fill {
+                color: [0, 0, 0, 160],
+            },
+        );
+        ui.set_layout(strip, FlexView::default());
+        let input = ui.add(Some(strip), Rect::default(), TextField::new(style));
+        ui.set_flex(input, FlexItem::grow(1.0));
+
+        Self { frame, log, input }
+    }
+
+    /// In the bottom-left corner of a view of `size`, its bottom edge at
+    /// `bottom`.
+    pub fn fit(&self, ui: &mut Ui, size: Vec2, bottom: f32) {
+        let size = Self::SIZE.min(size);
+        ui.set_rect(self.frame, Rect::new(vec2(0.0, bottom - size.y), size));
+    }
+
+    pub fn push(&self, ui: &mut Ui, kind: ChatKind, text: impl Into<String>) {
+        if let Some(log) = ui.get_mut::<ChatLog>(self.log) {
+            log.push(kind, text);
+        }
+    }
+}
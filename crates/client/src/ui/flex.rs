@@ -0,0 +1,197 @@
+use glam::{vec2, Vec2};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Row,
+    Column,
+}
+
+/// Where items go along the main axis when there's room left.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Justify {
+    #[default]
+    Start,
+    Center,
+    End,
+    SpaceBetween,
+    SpaceAround,
+}
+
+/// Where items go across the main axis.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    #[default]
+    Stretch,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Edges {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Edges {
+    pub fn all(value: f32) -> Self {
+        Self {
+            left: value,
+            top: value,
+            right: value,
+            bottom: value,
+        }
+    }
+
+    pub fn top_left(&self) -> Vec2 {
+        vec2(self.left, self.top)
+    }
+
+    pub fn size(&self) -> Vec2 {
+        vec2(self.left + self.right, self.top + self.bottom)
+    }
+}
+
+/// Lays a widget's children out in a row or column, see [`super::Ui::layout`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FlexView {
+    pub direction: Direction,
+    pub gap: f32,
+    pub padding: Edges,
+    pub justify: Justify,
+    pub align: Align,
+}
+
+/// How a widget is sized inside a [`FlexView`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexItem {
+    /// Share of the room left over.
+    pub grow: f32,
+    /// Share of the overflow given up, weighted by size.
+    pub shrink: f32,
+    /// Fixed sizes, the widget's own measure otherwise.
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    /// Overrides the container's [`Align`].
+    pub align: Option<Align>,
+}
+
+impl Default for FlexItem {
+    fn default() -> Self {
+        Self {
+            grow: 0.0,
+            shrink: 1.0,
+            width: None,
+            height: None,
+            align: None,
+        }
+    }
+}
+
+impl FlexItem {
+    pub fn fixed(size: Vec2) -> Self {
+        Self {
+            width: Some(size.x),
+            height: Some(size.y),
+            ..Default::default()
+        }
+    }
+
+    pub fn grow(grow: f32) -> Self {
+        Self {
+            grow,
+            ..Default::default()
+        }
+    }
+
+    /// The fixed sizes over `measured`.
+    pub fn size(&self, measured: Vec2) -> Vec2 {
+        vec2(
+            self.width.unwrap_or(measured.x),
+            self.height.unwrap_or(measured.y),
+        )
+    }
+}
+
+impl FlexView {
+    fn axis(&self) -> usize {
+        match self.direction {
+            Direction::Row => 0,
+            Direction::Column => 1,
+        }
+    }
+
+    /// The size fitting `items` without growing or shrinking them.
+    pub fn measure(&self, items: &[Vec2]) -> Vec2 {
+        let (main, cross) = (self.axis(), 1 - self.axis());
+        let mut size = Vec2::ZERO;
+        for item in items {
+            size[main] += item[main];
+            size[cross] = size[cross].max(item[cross]);
+        }
+        size[main] += self.gap * items.len().saturating_sub(1) as f32;
+        size + self.padding.size()
+    }
+
+    /// Positions of `items`, given as their item settings and base sizes,
+    /// within a container of `size`.
+    pub fn solve(&self, size: Vec2, items: &[(FlexItem, Vec2)]) -> Vec<(Vec2, Vec2)> {
+        let (main, cross) = (self.axis(), 1 - self.axis());
+        let inner = size - self.padding.size();
+        let mut sizes: Vec<Vec2> = items.iter().map(|(_, size)| *size).collect();
+
+        let used = sizes.iter().map(|size| size[main]).sum::<f32>()
+            + self.gap * items.len().saturating_sub(1) as f32;
+        let mut free = inner[main] - used;
+        let grow: f32 = items.iter().map(|(item, _)| item.grow).sum();
+        let shrink: f32 = items
+            .iter()
+            .map(|(item, size)| item.shrink * size[main])
+            .sum();
+        if free > 0.0 && grow > 0.0 {
+            for ((item, _), size) in items.iter().zip(&mut sizes) {
+                size[main] += free * item.grow / grow;
+            }
+            free = 0.0;
+        } else if free < 0.0 && shrink > 0.0 {
+            for ((item, base), size) in items.iter().zip(&mut sizes) {
+                size[main] = (size[main] + free * item.shrink * base[main] / shrink).max(0.0);
+            }
+            free = 0.0;
+        }
+        let free = free.max(0.0);
+
+        let count = items.len() as f32;
+        let (mut offset, between) = match self.justify {
+            Justify::Start => (0.0, 0.0),
+            Justify::Center => (free / 2.0, 0.0),
+            Justify::End => (free, 0.0),
+            Justify::SpaceBetween if count > 1.0 => (0.0, free / (count - 1.0)),
+            Justify::SpaceBetween => (0.0, 0.0),
+            Justify::SpaceAround => (free / count / 2.0, free / count),
+        };
+
+        let mut rects = Vec::with_capacity(items.len());
+        for ((item, _), mut size) in items.iter().zip(sizes) {
+            let mut position = Vec2::ZERO;
+            position[main] = offset;
+            match item.align.unwrap_or(self.align) {
+                Align::Start => {}
+                Align::Center => position[cross] = (inner[cross] - size[cross]) / 2.0,
+                Align::End => position[cross] = inner[cross] - size[cross],
+                Align::Stretch => {
+                    let fixed = [item.width, item.height][cross];
+                    if fixed.is_none() {
+                        size[cross] = inner[cross];
+                    }
+                }
+            }
+            rects.push((self.padding.top_left() + position, size));
+            offset += size[main] + self.gap + between;
+        }
+        rects
+    }
+}
@@ -0,0 +1,70 @@
+use glam::Vec2;
+
+use super::{Rect, Widget};
+use crate::map::Minimap;
+use crate::sdl::Renderer;
+use crate::sprite::Sprite;
+use crate::wz::Node;
+
+/// The map's minimap with markers for who and what is where, from
+/// `Map/MapHelper.img/minimap`.
+pub struct MinimapView {
+    pub minimap: Minimap,
+    user: Option<Sprite>,
+    npc: Option<Sprite>,
+    portal: Option<Sprite>,
+    another: Option<Sprite>,
+    /// World positions, as of the last frame.
+    pub player: Vec2,
+    pub others: Vec<Vec2>,
+    pub npcs: Vec<Vec2>,
+    pub portals: Vec<Vec2>,
+}
+
+impl MinimapView {
+    pub fn new(root: &Node, minimap: Minimap) -> Self {
+        let markers = root.at_path("Map/MapHelper.img/minimap").ok();
+        let marker = |name: &str| markers.as_ref()?.try_get(name).map(Into::into);
+        Self {
+            minimap,
+            user: marker("user"),
+            npc: marker("npc"),
+            portal: marker("portal"),
+            another: marker("another"),
+            player: Vec2::ZERO,
+            others: vec![],
+            npcs: vec![],
+            portals: vec![],
+        }
+    }
+}
+
+impl Widget for MinimapView {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        let canvas = &self.minimap.canvas;
+        renderer.draw(canvas, rect.position + canvas.origin);
+
+        // the player over everyone else
+        let markers = [
+            (&self.portal, &self.portals),
+            (&self.npc, &self.npcs),
+            (&self.another, &self.others),
+            (&self.user, &vec![self.player]),
+        ];
+        for (sprite, positions) in markers {
+            let Some(sprite) = sprite else {
+                continue;
+            };
+            for position in positions {
+                let position = self.minimap.to_minimap(*position);
+                // markers at the edges stay on the canvas
+                let position = position.clamp(Vec2::ZERO, canvas.size);
+                renderer.draw(sprite, rect.position + position);
+            }
+        }
+    }
+
+    fn measure(&self, _renderer: &Renderer) -> Vec2 {
+        self.minimap.canvas.size
+    }
+}
@@ -0,0 +1,591 @@
+use glam::Vec2;
+use sdl_sys::Event;
+use std::any::Any;
+
+use crate::sdl::Renderer;
+
+pub use button::{Button, ButtonState};
+pub use chat::{Chat, ChatKind, ChatLog, ChatMessage};
+pub use flex::{Align, Direction, Edges, FlexItem, FlexView, Justify};
+pub use minimap::MinimapView;
+pub use status_bar::{NumberFont, QuickSlots, StatusBar, StatusView};
+pub use text_field::{TextField, TextFieldAction};
+pub use tooltip::{Span, Tooltip, TooltipContent};
+pub use widget::{Fill, Frame, Image, Label};
+pub use window::{Window, WindowPositions};
+pub use world_map::WorldMapView;
+
+mod button;
+mod chat;
+mod flex;
+mod minimap;
+mod status_bar;
+mod text_field;
+mod tooltip;
+mod widget;
+mod window;
+mod world_map;
+
+// SDL_BUTTON_LEFT
+const LEFT_BUTTON: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WidgetId(usize);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub position: Vec2,
+    pub size: Vec2,
+}
+
+impl Rect {
+    pub fn new(position: Vec2, size: Vec2) -> Self {
+        Self { position, size }
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.position).all() && point.cmplt(self.position + self.size).all()
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.position + self.size / 2.0
+    }
+}
+
+/// What the [`Ui`] tells a widget, with points relative to its rect.
+#[derive(Debug, Clone, Copy)]
+pub enum UiEvent<'a> {
+    /// The pointer came over the widget, still held down if it was pressed
+    /// on it.
+    Enter {
+        pressed: bool,
+    },
+    Leave,
+    Move(Vec2),
+    Press(Vec2),
+    /// The press on the widget ended, over it or not.
+    Release {
+        point: Vec2,
+        inside: bool,
+    },
+    Click,
+    Focus,
+    Blur,
+    Enabled(bool),
+    /// The wheel turned over the widget, positive away from the user.
+    Scroll(f32),
+    /// Keyboard and text input, while focused.
+    Key(&'a Event),
+}
+
+pub trait AsAny {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+pub trait Widget: AsAny {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect);
+
+    /// Whether `point`, relative to the widget, is on it. The whole rect by
+    /// default.
+    fn hit(&self, size: Vec2, point: Vec2) -> bool {
+        point.cmpge(Vec2::ZERO).all() && point.cmplt(size).all()
+    }
+
+    fn event(&mut self, _event: &UiEvent) {}
+
+    /// Advances animations by `delta` ms.
+    fn tick(&mut self, _delta: f32) {}
+
+    /// What to show while the pointer rests on the widget.
+    fn tooltip(&self) -> Option<TooltipContent> {
+        None
+    }
+
+    /// The size the widget would like, when a [`FlexView`] lays it out.
+    fn measure(&self, _renderer: &Renderer) -> Vec2 {
+        Vec2::ZERO
+    }
+
+    /// Whether pressing the widget gives it the keyboard.
+    fn focusable(&self) -> bool {
+        false
+    }
+}
+
+/// Groups children without drawing anything itself.
+pub struct Panel;
+
+impl Widget for Panel {
+    fn draw(&mut self, _renderer: &mut Renderer, _rect: Rect) {}
+}
+
+type Callback = Box<dyn FnMut(&mut Ui)>;
+
+struct Entry {
+    widget: Box<dyn Widget>,
+    parent: Option<WidgetId>,
+    children: Vec<WidgetId>,
+    // relative to the parent
+    rect: Rect,
+    visible: bool,
+    enabled: bool,
+    on_click: Option<Callback>,
+    // lays the children out when set
+    layout: Option<FlexView>,
+    flex: FlexItem,
+    // the window dragging this moves
+    drag: Option<WidgetId>,
+}
+
+/// A tree of widgets under z-ordered windows, routing pointer and keyboard
+/// input to them.
+#[derive(Default)]
+pub struct Ui {
+    entries: Vec<Option<Entry>>,
+    // top-level widgets, back to front
+    windows: Vec<WidgetId>,
+    hovered: Option<WidgetId>,
+    pressed: Option<WidgetId>,
+    focus: Option<WidgetId>,
+    // the window being dragged and where it was grabbed, relative to it
+    dragging: Option<(WidgetId, Vec2)>,
+    // windows are kept inside
+    view: Vec2,
+    // something changed that affects layout
+    dirty: bool,
+}
+
+impl Ui {
+    /// Adds `widget` under `parent`, or as a window on top of the others.
+    pub fn add(
+        &mut self,
+        parent: Option<WidgetId>,
+        rect: Rect,
+        widget: impl Widget + 'static,
+    ) -> WidgetId {
+        self.insert(parent, usize::MAX, rect, widget)
+    }
+
+    /// Like [`Ui::add`], but `index` among its siblings.
+    pub fn insert(
+        &mut self,
+        parent: Option<WidgetId>,
+        index: usize,
+        rect: Rect,
+        widget: impl Widget + 'static,
+    ) -> WidgetId {
+        let id = WidgetId(self.entries.len());
+        self.entries.push(Some(Entry {
+            widget: Box::new(widget),
+            parent,
+            children: vec![],
+            rect,
+            visible: true,
+            enabled: true,
+            on_click: None,
+            layout: None,
+            flex: FlexItem::default(),
+            drag: None,
+        }));
+        let siblings = match parent {
+            Some(parent) => &mut self.entry_mut(parent).children,
+            None => &mut self.windows,
+        };
+        siblings.insert(index.min(siblings.len()), id);
+        self.dirty = true;
+        id
+    }
+
+    pub fn children(&self, id: WidgetId) -> &[WidgetId] {
+        &self.entry(id).children
+    }
+
+    /// Removes `id` and everything under it.
+    pub fn remove(&mut self, id: WidgetId) {
+        let Some(Some(entry)) = self.entries.get(id.0) else {
+            return;
+        };
+        match entry.parent {
+            Some(parent) => self.entry_mut(parent).children.retain(|item| *item != id),
+            None => self.windows.retain(|item| *item != id),
+        }
+        self.free(id);
+        self.dirty = true;
+    }
+
+    // drops `id` and everything under it, already unlinked from its parent
+    fn free(&mut self, id: WidgetId) {
+        let Some(entry) = self.entries.get_mut(id.0).and_then(Option::take) else {
+            return;
+        };
+        for child in entry.children {
+            self.free(child);
+        }
+        for state in [&mut self.hovered, &mut self.pressed, &mut self.focus] {
+            if *state == Some(id) {
+                *state = None;
+            }
+        }
+        if self.dragging.is_some_and(|(window, _)| window == id) {
+            self.dragging = None;
+        }
+    }
+
+    fn entry(&self, id: WidgetId) -> &Entry {
+        self.entries[id.0].as_ref().unwrap()
+    }
+
+    fn entry_mut(&mut self, id: WidgetId) -> &mut Entry {
+        self.entries[id.0].as_mut().unwrap()
+    }
+
+    fn send(&mut self, id: WidgetId, event: UiEvent) {
+        if let Some(Some(entry)) = self.entries.get_mut(id.0) {
+            entry.widget.event(&event);
+        }
+    }
+
+    pub fn get_mut<T: Widget + 'static>(&mut self, id: WidgetId) -> Option<&mut T> {
+        let entry = self.entries.get_mut(id.0)?.as_mut()?;
+        entry.widget.as_mut().as_any_mut().downcast_mut()
+    }
+
+    pub fn on_click(&mut self, id: WidgetId, callback: impl FnMut(&mut Ui) + 'static) {
+        self.entry_mut(id).on_click = Some(Box::new(callback));
+    }
+
+    pub fn rect(&self, id: WidgetId) -> Rect {
+        self.entry(id).rect
+    }
+
+    pub fn set_rect(&mut self, id: WidgetId, rect: Rect) {
+        self.entry_mut(id).rect = rect;
+    }
+
+    /// The rect in view coordinates.
+    pub fn absolute_rect(&self, id: WidgetId) -> Rect {
+        let entry = self.entry(id);
+        let mut rect = entry.rect;
+        if let Some(parent) = entry.parent {
+            rect.position += self.absolute_rect(parent).position;
+        }
+        rect
+    }
+
+    pub fn set_layout(&mut self, id: WidgetId, layout: FlexView) {
+        self.entry_mut(id).layout = Some(layout);
+        self.dirty = true;
+    }
+
+    pub fn set_flex(&mut self, id: WidgetId, flex: FlexItem) {
+        self.entry_mut(id).flex = flex;
+        self.dirty = true;
+    }
+
+    /// Whether widgets were added, removed, shown, hidden or resized since
+    /// the last [`Ui::layout`].
+    pub fn needs_layout(&self) -> bool {
+        self.dirty
+    }
+
+    /// The size `id` would like: what its children need under a
+    /// [`FlexView`], the widget's own measure otherwise.
+    pub fn measure(&self, id: WidgetId, renderer: &Renderer) -> Vec2 {
+        let entry = self.entry(id);
+        match &entry.layout {
+            Some(layout) => {
+                let items: Vec<Vec2> = self
+                    .flex_children(id)
+                    .map(|child| self.base_size(child, renderer))
+                    .collect();
+                layout.measure(&items)
+            }
+            None => entry.widget.measure(renderer),
+        }
+    }
+
+    // the size before growing or shrinking
+    fn base_size(&self, id: WidgetId, renderer: &Renderer) -> Vec2 {
+        let flex = self.entry(id).flex;
+        match (flex.width, flex.height) {
+            (Some(width), Some(height)) => Vec2::new(width, height),
+            _ => flex.size(self.measure(id, renderer)),
+        }
+    }
+
+    // hidden children take no room
+    fn flex_children(&self, id: WidgetId) -> impl Iterator<Item = WidgetId> + '_ {
+        self.entry(id)
+            .children
+            .iter()
+            .copied()
+            .filter(|child| self.entry(*child).visible)
+    }
+
+    /// Sizes and positions everything under a [`FlexView`], leaving the
+    /// windows themselves where they are.
+    pub fn layout(&mut self, renderer: &Renderer) {
+        self.dirty = false;
+        for id in self.windows.clone() {
+            self.layout_in(id, renderer);
+        }
+    }
+
+    fn layout_in(&mut self, id: WidgetId, renderer: &Renderer) {
+        let entry = self.entry(id);
+        if let Some(layout) = entry.layout {
+            let size = entry.rect.size;
+            let children: Vec<WidgetId> = self.flex_children(id).collect();
+            let items: Vec<(FlexItem, Vec2)> = children
+                .iter()
+                .map(|child| (self.entry(*child).flex, self.base_size(*child, renderer)))
+                .collect();
+            for (child, (position, size)) in children.iter().zip(layout.solve(size, &items)) {
+                self.set_rect(*child, Rect::new(position, size));
+            }
+        }
+        for child in self.entry(id).children.clone() {
+            self.layout_in(child, renderer);
+        }
+    }
+
+    /// Makes dragging `handle` move `window`.
+    pub fn set_drag_handle(&mut self, handle: WidgetId, window: WidgetId) {
+        self.entry_mut(handle).drag = Some(window);
+    }
+
+    /// Sets the view size windows are kept inside of.
+    pub fn set_view(&mut self, size: Vec2) {
+        self.view = size;
+        for id in self.windows.clone() {
+            let position = self.rect(id).position;
+            self.move_window(id, position);
+        }
+    }
+
+    // keeps the window inside the view, its top-left corner first
+    fn move_window(&mut self, id: WidgetId, position: Vec2) {
+        let view = self.view;
+        let rect = &mut self.entry_mut(id).rect;
+        let max = (view - rect.size).max(Vec2::ZERO);
+        rect.position = position.min(max).max(Vec2::ZERO);
+    }
+
+    pub fn visible(&self, id: WidgetId) -> bool {
+        self.entry(id).visible
+    }
+
+    pub fn set_visible(&mut self, id: WidgetId, visible: bool) {
+        if self.entry(id).visible != visible {
+            self.entry_mut(id).visible = visible;
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_enabled(&mut self, id: WidgetId, enabled: bool) {
+        if self.entry(id).enabled != enabled {
+            self.entry_mut(id).enabled = enabled;
+            self.send(id, UiEvent::Enabled(enabled));
+        }
+    }
+
+    pub fn focus(&self) -> Option<WidgetId> {
+        self.focus
+    }
+
+    pub fn set_focus(&mut self, focus: Option<WidgetId>) {
+        if self.focus == focus {
+            return;
+        }
+        if let Some(id) = self.focus {
+            self.send(id, UiEvent::Blur);
+        }
+        self.focus = focus;
+        if let Some(id) = focus {
+            self.send(id, UiEvent::Focus);
+        }
+    }
+
+    /// The window `id` is in.
+    pub fn window_of(&self, mut id: WidgetId) -> WidgetId {
+        while let Some(parent) = self.entry(id).parent {
+            id = parent;
+        }
+        id
+    }
+
+    /// Brings the window `id` is in to the front.
+    pub fn raise(&mut self, id: WidgetId) {
+        let window = self.window_of(id);
+        self.windows.retain(|item| *item != window);
+        self.windows.push(window);
+    }
+
+    /// The topmost visible widget under `point`.
+    pub fn hit(&self, point: Vec2) -> Option<WidgetId> {
+        self.windows
+            .iter()
+            .rev()
+            .find_map(|id| self.hit_in(*id, Vec2::ZERO, point))
+    }
+
+    fn hit_in(&self, id: WidgetId, offset: Vec2, point: Vec2) -> Option<WidgetId> {
+        let entry = self.entry(id);
+        if !entry.visible {
+            return None;
+        }
+        let position = offset + entry.rect.position;
+        // later children are drawn over earlier ones
+        let child = entry
+            .children
+            .iter()
+            .rev()
+            .find_map(|child| self.hit_in(*child, position, point));
+        child.or_else(|| {
+            entry
+                .widget
+                .hit(entry.rect.size, point - position)
+                .then_some(id)
+        })
+    }
+
+    /// Routes `event`, with pointer positions in logical units. Returns
+    /// whether the UI took it, so it shouldn't also reach the game.
+    pub fn event(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::MouseMotion { x, y } => {
+                let point = Vec2::new(x, y);
+                if let Some((window, grab)) = self.dragging {
+                    self.move_window(window, point - grab);
+                    return true;
+                }
+                let hit = self.hit(point);
+                if hit != self.hovered {
+                    if let Some(id) = self.hovered {
+                        self.send(id, UiEvent::Leave);
+                    }
+                    if let Some(id) = hit {
+                        let pressed = self.pressed == Some(id);
+                        self.send(id, UiEvent::Enter { pressed });
+                    }
+                    self.hovered = hit;
+                }
+                // presses follow the pointer even outside
+                for id in hit
+                    .into_iter()
+                    .chain(self.pressed.filter(|id| hit != Some(*id)))
+                {
+                    let local = point - self.absolute_rect(id).position;
+                    self.send(id, UiEvent::Move(local));
+                }
+                hit.is_some() || self.pressed.is_some()
+            }
+            Event::MouseButtonDown { button, x, y } if button == LEFT_BUTTON => {
+                let point = Vec2::new(x, y);
+                let Some(id) = self.hit(point) else {
+                    self.set_focus(None);
+                    return false;
+                };
+                self.raise(id);
+                if self.entry(id).enabled {
+                    self.pressed = Some(id);
+                    let local = point - self.absolute_rect(id).position;
+                    self.send(id, UiEvent::Press(local));
+                    let focus = self.entry(id).widget.focusable().then_some(id);
+                    self.set_focus(focus);
+                    if let Some(window) = self.entry(id).drag {
+                        let grab = point - self.rect(window).position;
+                        self.dragging = Some((window, grab));
+                    }
+                }
+                true
+            }
+            Event::MouseButtonUp { button, x, y } if button == LEFT_BUTTON => {
+                let point = Vec2::new(x, y);
+                let hit = self.hit(point);
+                self.dragging = None;
+                let Some(id) = self.pressed.take() else {
+                    return hit.is_some();
+                };
+                let inside = hit == Some(id);
+                let local = point - self.absolute_rect(id).position;
+                self.send(
+                    id,
+                    UiEvent::Release {
+                        point: local,
+                        inside,
+                    },
+                );
+                if inside {
+                    self.send(id, UiEvent::Click);
+                    // taken out while it runs, as it may change the tree
+                    if let Some(mut callback) = self.entry_mut(id).on_click.take() {
+                        callback(self);
+                        if let Some(Some(entry)) = self.entries.get_mut(id.0) {
+                            entry.on_click.get_or_insert(callback);
+                        }
+                    }
+                }
+                true
+            }
+            Event::MouseButtonDown { x, y, .. } | Event::MouseButtonUp { x, y, .. } => {
+                self.hit(Vec2::new(x, y)).is_some()
+            }
+            Event::MouseWheel { y, .. } => match self.hovered {
+                Some(id) => {
+                    self.send(id, UiEvent::Scroll(y));
+                    true
+                }
+                None => false,
+            },
+            Event::KeyDown { .. }
+            | Event::KeyUp { .. }
+            | Event::TextInput { .. }
+            | Event::TextEditing { .. } => match self.focus {
+                Some(id) => {
+                    self.send(id, UiEvent::Key(event));
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// The hovered widget's tooltip.
+    pub fn tooltip(&self) -> Option<TooltipContent> {
+        self.entry(self.hovered?).widget.tooltip()
+    }
+
+    /// Advances every widget's animations by `delta` ms.
+    pub fn tick(&mut self, delta: f32) {
+        for entry in self.entries.iter_mut().flatten() {
+            entry.widget.tick(delta);
+        }
+    }
+
+    /// Draws the visible windows back to front.
+    pub fn draw(&mut self, renderer: &mut Renderer) {
+        for id in self.windows.clone() {
+            self.draw_in(renderer, id, Vec2::ZERO);
+        }
+    }
+
+    fn draw_in(&mut self, renderer: &mut Renderer, id: WidgetId, offset: Vec2) {
+        let entry = self.entry_mut(id);
+        if !entry.visible {
+            return;
+        }
+        let rect = Rect::new(offset + entry.rect.position, entry.rect.size);
+        entry.widget.draw(renderer, rect);
+        for child in entry.children.clone() {
+            self.draw_in(renderer, child, rect.position);
+        }
+    }
+}
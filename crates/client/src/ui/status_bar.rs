@@ -0,0 +1,361 @@
+use glam::{vec2, Vec2};
+use sdl_sys::SDL_FRect;
+use std::collections::HashMap;
+
+use super::{Align, Button, Edges, FlexItem, FlexView, Rect, Ui, Widget, WidgetId};
+use crate::ecs::Stats;
+use crate::sdl::{DrawParams, Renderer, TextStyle};
+use crate::sprite::Sprite;
+use crate::wz::Node;
+
+/// Digit and symbol sprites, by the character they stand for.
+#[derive(Default)]
+pub struct NumberFont {
+    glyphs: HashMap<char, Sprite>,
+}
+
+impl From<Node> for NumberFont {
+    fn from(node: Node) -> Self {
+        let glyphs = node
+            .children()
+            .into_iter()
+            .filter_map(|(name, node)| {
+                let c = match name.as_str() {
+                    "Lbracket" => '[',
+                    "Rbracket" => ']',
+                    "slash" => '/',
+                    "percent" => '%',
+                    "dot" => '.',
+                    name if name.len() == 1 => name.chars().next()?,
+                    _ => return None,
+                };
+                Some((c, node.into()))
+            })
+            .collect();
+        Self { glyphs }
+    }
+}
+
+impl NumberFont {
+    /// Whether every character of `text` has a sprite.
+    pub fn covers(&self, text: &str) -> bool {
+        text.chars().all(|c| self.glyphs.contains_key(&c))
+    }
+
+    pub fn width(&self, text: &str) -> f32 {
+        text.chars()
+            .filter_map(|c| self.glyphs.get(&c))
+            .map(|sprite| sprite.size.x + 1.0)
+            .sum()
+    }
+
+    /// Draws `text` with its top-left corner at `position`, falling back to
+    /// the regular font for characters without sprites.
+    pub fn draw(&self, renderer: &mut Renderer, text: &str, position: Vec2) {
+        if !self.covers(text) {
+            renderer.draw_text(text, position);
+            return;
+        }
+        let mut x = position.x;
+        for c in text.chars() {
+            let sprite = &self.glyphs[&c];
+            renderer.draw(sprite, vec2(x, position.y) + sprite.origin);
+            x += sprite.size.x + 1.0;
+        }
+    }
+}
+
+// the bar's backdrop, dark where its sprite doesn't reach
+struct Background {
+    sprite: Option<Sprite>,
+}
+
+impl Widget for Background {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        renderer.fill_rect(
+            &SDL_FRect {
+                x: rect.position.x,
+                y: rect.position.y,
+                w: rect.size.x,
+                h: rect.size.y,
+            },
+            [32, 32, 40, 230],
+        );
+        if let Some(sprite) = &self.sprite {
+            renderer.draw(sprite, rect.position + sprite.origin);
+        }
+    }
+}
+
+/// Level, name and job, then the HP, MP and EXP gauges.
+pub struct StatusView {
+    pub stats: Stats,
+    pub name: String,
+    level: NumberFont,
+    numbers: NumberFont,
+}
+
+impl StatusView {
+    const INFO_WIDTH: f32 = 140.0;
+    const GAUGE_HEIGHT: f32 = 12.0;
+
+    fn gauge(
+        &self,
+        renderer: &mut Renderer,
+        position: Vec2,
+        width: f32,
+        ratio: f32,
+        color: [u8; 4],
+        text: &str,
+    ) {
+        let rect = SDL_FRect {
+            x: position.x,
+            y: position.y,
+            w: width,
+            h: Self::GAUGE_HEIGHT,
+        };
+        renderer.fill_rect(&rect, [64, 64, 64, 255]);
+        renderer.fill_rect(
+            &SDL_FRect {
+                w: width * ratio.clamp(0.0, 1.0),
+                ..rect
+            },
+            color,
+        );
+        renderer.render_rect(&rect, [0, 0, 0, 255]);
+        let x = position.x + (width - self.numbers.width(text)) / 2.0;
+        self.numbers.draw(renderer, text, vec2(x, position.y + 1.0));
+    }
+}
+
+impl Widget for StatusView {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        let Stats {
+            level,
+            hp,
+            max_hp,
+            mp,
+            max_mp,
+            exp,
+            max_exp,
+            ..
+        } = self.stats;
+
+        let level = level.to_string();
+        let position = rect.position + vec2(8.0, 8.0);
+        renderer.draw_text("Lv.", position);
+        self.level
+            .draw(renderer, &level, position + vec2(28.0, 0.0));
+        let style = TextStyle {
+            color: [255, 255, 255, 255],
+            ..Default::default()
+        };
+        for (index, text) in [self.name.as_str(), self.stats.job_name()]
+            .iter()
+            .enumerate()
+        {
+            if text.is_empty() {
+                continue;
+            }
+            let texture = renderer.text(text, style);
+            let y = rect.position.y + 28.0 + index as f32 * 16.0;
+            renderer.render_texture(
+                &texture,
+                vec2(position.x, y),
+                Vec2::ZERO,
+                None,
+                DrawParams::default(),
+            );
+        }
+
+        // HP and MP a quarter each, EXP the rest
+        let left = rect.position.x + Self::INFO_WIDTH;
+        let width = (rect.size.x - Self::INFO_WIDTH - 16.0).max(0.0);
+        let y = rect.position.y + (rect.size.y - Self::GAUGE_HEIGHT) / 2.0;
+        let ratio = |value: u64, max: u64| {
+            if max > 0 {
+                value as f32 / max as f32
+            } else {
+                0.0
+            }
+        };
+        let exp_percent = ratio(exp, max_exp) * 100.0;
+        let gauges = [
+            (
+                width / 4.0,
+                ratio(hp as u64, max_hp as u64),
+                [230, 40, 40, 255],
+                format!("[{hp}/{max_hp}]"),
+            ),
+            (
+                width / 4.0,
+                ratio(mp as u64, max_mp as u64),
+                [40, 90, 230, 255],
+                format!("[{mp}/{max_mp}]"),
+            ),
+            (
+                width / 2.0,
+                ratio(exp, max_exp),
+                [230, 210, 40, 255],
+                format!("{exp}[{exp_percent:.2}%]"),
+            ),
+        ];
+        let mut x = left;
+        for (width, ratio, color, text) in gauges {
+            self.gauge(renderer, vec2(x, y), width - 4.0, ratio, color, &text);
+            x += width;
+        }
+    }
+}
+
+/// Room for skills and items bound to keys.
+pub struct QuickSlots {
+    background: Option<Sprite>,
+    pub icons: [Option<Sprite>; QuickSlots::COUNT],
+}
+
+impl QuickSlots {
+    pub const COUNT: usize = 8;
+    const COLUMNS: usize = 4;
+    const SLOT: f32 = 34.0;
+}
+
+impl Widget for QuickSlots {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        match &self.background {
+            Some(sprite) => renderer.draw(sprite, rect.position + sprite.origin),
+            None => {
+                for index in 0..Self::COUNT {
+                    let (column, row) = (index % Self::COLUMNS, index / Self::COLUMNS);
+                    renderer.render_rect(
+                        &SDL_FRect {
+                            x: rect.position.x + column as f32 * Self::SLOT,
+                            y: rect.position.y + row as f32 * Self::SLOT,
+                            w: Self::SLOT - 2.0,
+                            h: Self::SLOT - 2.0,
+                        },
+                        [160, 160, 160, 255],
+                    );
+                }
+            }
+        }
+        for (index, icon) in self.icons.iter().enumerate() {
+            let Some(icon) = icon else {
+                continue;
+            };
+            let (column, row) = (index % Self::COLUMNS, index / Self::COLUMNS);
+            let slot = vec2(column as f32, row as f32) * Self::SLOT;
+            renderer.draw(icon, rect.position + slot + vec2(1.0, Self::SLOT - 1.0));
+        }
+    }
+
+    fn measure(&self, _renderer: &Renderer) -> Vec2 {
+        self.background.as_ref().map_or(
+            vec2(Self::COLUMNS as f32, (Self::COUNT / Self::COLUMNS) as f32) * Self::SLOT,
+            |sprite| sprite.size,
+        )
+    }
+}
+
+/// The bar along the bottom of the view, from `UI/StatusBar.img`.
+pub struct StatusBar {
+    pub frame: WidgetId,
+    pub status: WidgetId,
+    pub quick_slots: WidgetId,
+    /// Menu buttons, by name, for the ones the data has.
+    pub menu: Vec<(String, WidgetId)>,
+    height: f32,
+}
+
+impl StatusBar {
+    const MENU: [&'static str; 6] = [
+        "BtCashShop",
+        "BtMenu",
+        "BtShort",
+        "BtSystem",
+        "BtClaim",
+        "BtChat",
+    ];
+
+    pub fn new(ui: &mut Ui, root: &Node) -> Self {
+        let node = root.at_path("UI/StatusBar.img").ok();
+        let get = |path: &str| node.as_ref()?.at_path(path).ok();
+
+        let background: Option<Sprite> = get("base/backgrnd").map(Into::into);
+        let height = background.as_ref().map_or(71.0, |sprite| sprite.size.y);
+        let frame = ui.add(None, Rect::default(), Background { sprite: background });
+        ui.set_layout(
+            frame,
+            FlexView {
+                gap: 6.0,
+                padding: Edges {
+                    right: 6.0,
+                    ..Default::default()
+                },
+                align: Align::Center,
+                ..Default::default()
+            },
+        );
+
+        let status = ui.add(
+            Some(frame),
+            Rect::default(),
+            StatusView {
+                stats: Stats::default(),
+                name: String::new(),
+                level: root
+                    .at_path("UI/Basic.img/LevelNo")
+                    .map(Into::into)
+                    .unwrap_or_default(),
+                numbers: get("number").map(Into::into).unwrap_or_default(),
+            },
+        );
+        ui.set_flex(
+            status,
+            FlexItem {
+                grow: 1.0,
+                height: Some(height),
+                ..Default::default()
+            },
+        );
+        let quick_slots = ui.add(
+            Some(frame),
+            Rect::default(),
+            QuickSlots {
+                background: get("base/quickSlot").map(Into::into),
+                icons: Default::default(),
+            },
+        );
+
+        let menu = Self::MENU
+            .iter()
+            .filter_map(|name| {
+                let button: Button = get(name)?.into();
+                Some((
+                    name.to_string(),
+                    ui.add(Some(frame), Rect::default(), button),
+                ))
+            })
+            .collect();
+
+        Self {
+            frame,
+            status,
+            quick_slots,
+            menu,
+            height,
+        }
+    }
+
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// Spans the bottom of a view of `size`.
+    pub fn fit(&self, ui: &mut Ui, size: Vec2) {
+        ui.set_rect(
+            self.frame,
+            Rect::new(vec2(0.0, size.y - self.height), vec2(size.x, self.height)),
+        );
+    }
+}
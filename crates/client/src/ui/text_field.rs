@@ -0,0 +1,278 @@
+use glam::{vec2, Vec2};
+use sdl_sys::{Event, SDL_Scancode};
+
+use super::{Rect, UiEvent, Widget};
+use crate::sdl::{DrawParams, Renderer, TextStyle};
+
+/// What a [`TextField`] wants its owner to do, see
+/// [`TextField::take_action`].
+pub enum TextFieldAction {
+    /// Enter was pressed with this text, which may be empty.
+    Submit(String),
+    /// Escape was pressed.
+    Cancel,
+}
+
+/// A single line of text typed through SDL's text input, so IMEs work. What
+/// the IME is still composing shows underlined at the caret, and up/down
+/// recall earlier lines.
+pub struct TextField {
+    pub text: String,
+    pub style: TextStyle,
+    /// In characters.
+    pub max_length: usize,
+    // byte offset into `text`
+    cursor: usize,
+    // from the IME, with its own caret in characters
+    composition: String,
+    composition_cursor: usize,
+    history: Vec<String>,
+    // the history entry shown, while recalling
+    recall: Option<usize>,
+    // what was typed before recalling started
+    draft: String,
+    focused: bool,
+    blink: f32,
+    caret: f32,
+    // the line last laid out, with its character boundaries in bytes and
+    // the caret's offset at each
+    laid_out: String,
+    boundaries: Vec<usize>,
+    offsets: Vec<f32>,
+    action: Option<TextFieldAction>,
+}
+
+impl TextField {
+    const HISTORY: usize = 64;
+    const BLINK: f32 = 500.0;
+    const PADDING: f32 = 2.0;
+
+    pub fn new(style: TextStyle) -> Self {
+        Self {
+            text: String::new(),
+            style,
+            max_length: 70,
+            cursor: 0,
+            composition: String::new(),
+            composition_cursor: 0,
+            history: Vec::new(),
+            recall: None,
+            draft: String::new(),
+            focused: false,
+            blink: 0.0,
+            caret: Self::PADDING,
+            laid_out: String::new(),
+            boundaries: vec![0],
+            offsets: vec![0.0],
+            action: None,
+        }
+    }
+
+    pub fn take_action(&mut self) -> Option<TextFieldAction> {
+        self.action.take()
+    }
+
+    /// The caret's offset from the field's left edge, as last drawn.
+    pub fn caret(&self) -> f32 {
+        self.caret
+    }
+
+    fn insert(&mut self, text: &str) {
+        let room = self.max_length.saturating_sub(self.text.chars().count());
+        for c in text.chars().filter(|c| !c.is_control()).take(room) {
+            self.text.insert(self.cursor, c);
+            self.cursor += c.len_utf8();
+        }
+        self.recall = None;
+    }
+
+    fn previous(&self) -> Option<usize> {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(index, _)| index)
+    }
+
+    fn next(&self) -> Option<usize> {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map(|c| self.cursor + c.len_utf8())
+    }
+
+    fn recall(&mut self, older: bool) {
+        let index = match self.recall {
+            None if older => match self.history.len().checked_sub(1) {
+                Some(index) => {
+                    self.draft = std::mem::take(&mut self.text);
+                    index
+                }
+                None => return,
+            },
+            None => return,
+            Some(index) if older => index.saturating_sub(1),
+            Some(index) if index + 1 < self.history.len() => index + 1,
+            // past the newest, back to the draft
+            Some(_) => {
+                self.recall = None;
+                self.text = std::mem::take(&mut self.draft);
+                self.cursor = self.text.len();
+                return;
+            }
+        };
+        self.recall = Some(index);
+        self.text = self.history[index].clone();
+        self.cursor = self.text.len();
+    }
+
+    fn submit(&mut self) {
+        let text = std::mem::take(&mut self.text);
+        if !text.is_empty() && self.history.last() != Some(&text) {
+            if self.history.len() == Self::HISTORY {
+                self.history.remove(0);
+            }
+            self.history.push(text.clone());
+        }
+        self.cursor = 0;
+        self.recall = None;
+        self.draft.clear();
+        self.action = Some(TextFieldAction::Submit(text));
+    }
+
+    // measures `line` only when it changed since the last frame
+    fn lay_out(&mut self, renderer: &Renderer, line: String) {
+        if line == self.laid_out {
+            return;
+        }
+        self.boundaries = line
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain([line.len()])
+            .collect();
+        self.offsets = renderer.caret_offsets(&line, self.style);
+        self.laid_out = line;
+    }
+
+    fn key(&mut self, scancode: SDL_Scancode::Type) {
+        match scancode {
+            SDL_Scancode::SDL_SCANCODE_BACKSPACE => {
+                if let Some(previous) = self.previous() {
+                    self.text.replace_range(previous..self.cursor, "");
+                    self.cursor = previous;
+                }
+            }
+            SDL_Scancode::SDL_SCANCODE_DELETE => {
+                if let Some(next) = self.next() {
+                    self.text.replace_range(self.cursor..next, "");
+                }
+            }
+            SDL_Scancode::SDL_SCANCODE_LEFT => {
+                self.cursor = self.previous().unwrap_or(self.cursor);
+            }
+            SDL_Scancode::SDL_SCANCODE_RIGHT => {
+                self.cursor = self.next().unwrap_or(self.cursor);
+            }
+            SDL_Scancode::SDL_SCANCODE_HOME => self.cursor = 0,
+            SDL_Scancode::SDL_SCANCODE_END => self.cursor = self.text.len(),
+            SDL_Scancode::SDL_SCANCODE_UP => self.recall(true),
+            SDL_Scancode::SDL_SCANCODE_DOWN => self.recall(false),
+            SDL_Scancode::SDL_SCANCODE_RETURN | SDL_Scancode::SDL_SCANCODE_KP_ENTER => {
+                self.submit()
+            }
+            SDL_Scancode::SDL_SCANCODE_ESCAPE => self.action = Some(TextFieldAction::Cancel),
+            _ => {}
+        }
+    }
+}
+
+impl Widget for TextField {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        let (before, after) = self.text.split_at(self.cursor);
+        self.lay_out(renderer, format!("{before}{}{after}", self.composition));
+        // from here on in characters, indexing `boundaries` and `offsets`
+        let boundary = |index: usize| self.boundaries.binary_search(&index).unwrap_or(0);
+        let composing = boundary(before.len())..boundary(before.len() + self.composition.len());
+        let caret = composing
+            .start
+            .saturating_add(self.composition_cursor)
+            .min(composing.end);
+
+        // characters fall off the front to keep the caret in view, then off
+        // the back to fit
+        let width = rect.size.x - Self::PADDING * 2.0;
+        let offsets = &self.offsets;
+        let mut start = 0;
+        while start < caret && offsets[caret] - offsets[start] > width {
+            start += 1;
+        }
+        let mut end = offsets.len() - 1;
+        while end > caret && offsets[end] - offsets[start] > width {
+            end -= 1;
+        }
+
+        let origin = vec2(
+            rect.position.x + Self::PADDING,
+            rect.position.y + (rect.size.y - self.style.size as f32) / 2.0,
+        )
+        .round();
+        if start < end {
+            let line = &self.laid_out[self.boundaries[start]..self.boundaries[end]];
+            let texture = renderer.text(line, self.style);
+            renderer.render_texture(&texture, origin, Vec2::ZERO, None, DrawParams::default());
+        }
+        let x = |index: usize| origin.x + offsets[index.clamp(start, end)] - offsets[start];
+        let underline = origin.y + self.style.size as f32 + 1.0;
+        if !self.composition.is_empty() {
+            renderer.render_line(
+                vec2(x(composing.start), underline),
+                vec2(x(composing.end), underline),
+                self.style.color,
+            );
+        }
+        let caret = x(caret);
+        self.caret = caret - rect.position.x;
+        if self.focused && self.blink % (Self::BLINK * 2.0) < Self::BLINK {
+            renderer.render_line(
+                vec2(caret, origin.y),
+                vec2(caret, underline - 1.0),
+                self.style.color,
+            );
+        }
+    }
+
+    fn event(&mut self, event: &UiEvent) {
+        match event {
+            UiEvent::Focus => self.focused = true,
+            UiEvent::Blur => {
+                self.focused = false;
+                self.composition.clear();
+            }
+            UiEvent::Key(Event::TextInput { text }) => {
+                self.composition.clear();
+                self.insert(text);
+            }
+            UiEvent::Key(Event::TextEditing { text, start, .. }) => {
+                self.composition = text.clone();
+                self.composition_cursor = usize::try_from(*start).unwrap_or(usize::MAX);
+            }
+            // keys belong to the IME while it's composing
+            UiEvent::Key(Event::KeyDown { scancode, .. }) if self.composition.is_empty() => {
+                self.key(*scancode)
+            }
+            _ => return,
+        }
+        self.blink = 0.0;
+    }
+
+    fn tick(&mut self, delta: f32) {
+        self.blink += delta;
+    }
+
+    fn measure(&self, _renderer: &Renderer) -> Vec2 {
+        vec2(0.0, self.style.size as f32 + Self::PADDING * 2.0 + 2.0)
+    }
+
+    fn focusable(&self) -> bool {
+        true
+    }
+}
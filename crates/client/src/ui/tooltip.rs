@@ -0,0 +1,212 @@
+use glam::{vec2, Vec2};
+use image::DynamicImage;
+use std::sync::Arc;
+
+use crate::sdl::{DrawParams, NineGridTexture, Renderer, TextStyle};
+use crate::sprite::Sprite;
+
+#[derive(Clone)]
+pub enum Span {
+    Text { text: String, style: TextStyle },
+    Icon(Sprite),
+}
+
+impl PartialEq for Span {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Span::Text { text, style }, Span::Text { text: t, style: s }) => {
+                text == t && style == s
+            }
+            (Span::Icon(a), Span::Icon(b)) => a.path == b.path,
+            _ => false,
+        }
+    }
+}
+
+/// Lines of text in different styles, with icons in between.
+#[derive(Clone, Default, PartialEq)]
+pub struct TooltipContent {
+    pub lines: Vec<Vec<Span>>,
+}
+
+impl TooltipContent {
+    pub const TITLE: TextStyle = TextStyle {
+        size: 14,
+        color: [255, 255, 255, 255],
+        max_width: 300,
+    };
+    pub const BODY: TextStyle = TextStyle {
+        size: 12,
+        color: [255, 255, 255, 255],
+        max_width: 300,
+    };
+    pub const NOTE: TextStyle = TextStyle {
+        size: 12,
+        color: [187, 187, 187, 255],
+        max_width: 300,
+    };
+
+    /// Appends `text` to the last line.
+    pub fn text(mut self, text: &str, style: TextStyle) -> Self {
+        self.push(Span::Text {
+            text: text.to_string(),
+            style,
+        });
+        self
+    }
+
+    /// Appends `icon` to the last line.
+    pub fn icon(mut self, icon: Sprite) -> Self {
+        self.push(Span::Icon(icon));
+        self
+    }
+
+    /// Starts a new line.
+    pub fn line(mut self) -> Self {
+        self.lines.push(vec![]);
+        self
+    }
+
+    fn push(&mut self, span: Span) {
+        match self.lines.last_mut() {
+            Some(line) => line.push(span),
+            None => self.lines.push(vec![span]),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.iter().all(Vec::is_empty)
+    }
+}
+
+/// A box following the pointer, sized to what it says, shown after the
+/// pointer rests on something for a moment.
+pub struct Tooltip {
+    background: NineGridTexture,
+    content: Option<TooltipContent>,
+    // what the pointer is over, and for how long
+    hovered: Option<TooltipContent>,
+    elapsed: f32,
+    /// ms before showing.
+    pub show_delay: f32,
+    /// ms the last content lingers after the pointer leaves.
+    pub hide_delay: f32,
+}
+
+impl Tooltip {
+    const PADDING: Vec2 = vec2(6.0, 4.0);
+    const SPACING: f32 = 3.0;
+    // from the pointer's hot spot
+    const OFFSET: Vec2 = vec2(12.0, 16.0);
+
+    pub fn new(background: NineGridTexture) -> Self {
+        Self {
+            background,
+            content: None,
+            hovered: None,
+            elapsed: 0.0,
+            show_delay: 300.0,
+            hide_delay: 100.0,
+        }
+    }
+
+    /// The built-in dark box.
+    pub fn fallback() -> Self {
+        let image: DynamicImage = image::load_from_memory(include_bytes!("../tooltip.png"))
+            .unwrap()
+            .into();
+        Self::new(NineGridTexture {
+            image: Arc::new(image),
+            left_width: 4,
+            middle_width: 0,
+            right_width: 4,
+            top_height: 4,
+            middle_height: 0,
+            bottom_height: 4,
+        })
+    }
+
+    /// What's under the pointer this frame, restarting the delay when it
+    /// changes.
+    pub fn hover(&mut self, content: Option<TooltipContent>) {
+        let content = content.filter(|content| !content.is_empty());
+        if content != self.hovered {
+            self.hovered = content;
+            self.elapsed = 0.0;
+        }
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        self.elapsed += delta;
+        match &self.hovered {
+            Some(hovered) if self.elapsed >= self.show_delay => {
+                self.content = Some(hovered.clone());
+            }
+            None if self.elapsed >= self.hide_delay => {
+                self.content = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn measure_span(renderer: &Renderer, span: &Span) -> Vec2 {
+        match span {
+            Span::Text { text, style } => renderer.measure_text(text, *style),
+            Span::Icon(sprite) => sprite.size,
+        }
+    }
+
+    // each line's size, spans side by side
+    fn measure_lines(renderer: &Renderer, content: &TooltipContent) -> Vec<Vec2> {
+        content
+            .lines
+            .iter()
+            .map(|line| {
+                line.iter().fold(Vec2::ZERO, |size, span| {
+                    let span = Self::measure_span(renderer, span);
+                    let gap = if size.x > 0.0 { Self::SPACING } else { 0.0 };
+                    vec2(size.x + gap + span.x, size.y.max(span.y))
+                })
+            })
+            .collect()
+    }
+
+    /// Draws next to `mouse`, kept inside a view of `size`.
+    pub fn draw(&self, renderer: &mut Renderer, mouse: Vec2, size: Vec2) {
+        let Some(content) = &self.content else {
+            return;
+        };
+        let lines = Self::measure_lines(renderer, content);
+        let inner = lines.iter().fold(Vec2::ZERO, |total, line| {
+            vec2(total.x.max(line.x), total.y + line.y)
+        }) + vec2(0.0, Self::SPACING * lines.len().saturating_sub(1) as f32);
+        let box_size = inner + Self::PADDING * 2.0;
+        let position = (mouse + Self::OFFSET).min(size - box_size).max(Vec2::ZERO);
+        renderer.draw_nine_grid(&self.background, position, box_size, 255);
+
+        let mut caret = position + Self::PADDING;
+        for (line, line_size) in content.lines.iter().zip(&lines) {
+            let mut x = caret.x;
+            for span in line {
+                let span_size = Self::measure_span(renderer, span);
+                // spans sit centered on the line
+                let top = caret.y + (line_size.y - span_size.y) / 2.0;
+                match span {
+                    Span::Text { text, style } => {
+                        let texture = renderer.text(text, *style);
+                        renderer.render_texture(
+                            &texture,
+                            vec2(x, top),
+                            Vec2::ZERO,
+                            None,
+                            DrawParams::default(),
+                        );
+                    }
+                    Span::Icon(sprite) => renderer.draw(sprite, vec2(x, top) + sprite.origin),
+                }
+                x += span_size.x + Self::SPACING;
+            }
+            caret.y += line_size.y + Self::SPACING;
+        }
+    }
+}
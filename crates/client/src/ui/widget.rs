@@ -0,0 +1,110 @@
+use glam::Vec2;
+use sdl_sys::SDL_FRect;
+
+use super::{Rect, Widget};
+use crate::sdl::{DrawParams, NineGridTexture, Renderer, TextStyle};
+use crate::sprite::Sprite;
+
+/// A sprite with its top-left corner at the widget's.
+pub struct Image {
+    pub sprite: Sprite,
+}
+
+impl Image {
+    pub fn new(sprite: Sprite) -> Self {
+        Self { sprite }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.sprite.size
+    }
+}
+
+impl Widget for Image {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        renderer.draw(&self.sprite, rect.position + self.sprite.origin);
+    }
+
+    fn measure(&self, _renderer: &Renderer) -> Vec2 {
+        self.size()
+    }
+}
+
+/// A nine-grid stretched over the widget, like window borders.
+pub struct Frame {
+    pub grid: NineGridTexture,
+    pub alpha: i32,
+}
+
+impl Frame {
+    pub fn new(grid: NineGridTexture) -> Self {
+        Self { grid, alpha: 255 }
+    }
+}
+
+impl Widget for Frame {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        renderer.draw_nine_grid(&self.grid, rect.position, rect.size, self.alpha);
+    }
+}
+
+/// A solid color over the widget.
+pub struct Fill {
+    pub color: [u8; 4],
+}
+
+impl Widget for Fill {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        renderer.fill_rect(
+            &SDL_FRect {
+                x: rect.position.x,
+                y: rect.position.y,
+                w: rect.size.x,
+                h: rect.size.y,
+            },
+            self.color,
+        );
+    }
+}
+
+pub struct Label {
+    pub text: String,
+    pub style: TextStyle,
+}
+
+impl Label {
+    pub fn new(text: &str, style: TextStyle) -> Self {
+        Self {
+            text: text.to_string(),
+            style,
+        }
+    }
+}
+
+impl Widget for Label {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        if self.text.is_empty() {
+            return;
+        }
+        let texture = renderer.text(&self.text, self.style);
+        renderer.render_texture(
+            &texture,
+            rect.position,
+            Vec2::ZERO,
+            None,
+            DrawParams::default(),
+        );
+    }
+
+    fn measure(&self, renderer: &Renderer) -> Vec2 {
+        if self.text.is_empty() {
+            return Vec2::ZERO;
+        }
+        renderer.measure_text(&self.text, self.style)
+    }
+
+    // text doesn't take clicks from what's behind it
+    fn hit(&self, _size: Vec2, _point: Vec2) -> bool {
+        false
+    }
+}
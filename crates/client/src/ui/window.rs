@@ -0,0 +1,176 @@
+use glam::{vec2, Vec2};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::{
+    Align, Button, Direction, Edges, FlexItem, FlexView, Frame, Panel, Rect, Ui, Widget, WidgetId,
+};
+use crate::sdl::{NineGridTexture, Renderer};
+
+/// A framed window in a [`Ui`]: a title bar to drag it by, with a close
+/// button, over `content`.
+pub struct Window {
+    pub name: String,
+    /// The window itself, its border drawn as a nine-grid.
+    pub frame: WidgetId,
+    pub title_bar: WidgetId,
+    pub close: WidgetId,
+    pub content: WidgetId,
+    /// Where the window first opens, centered if unset.
+    pub home: Option<Vec2>,
+}
+
+impl Window {
+    // the title bar starts this far into the top border
+    const TITLE_TOP: f32 = 6.0;
+
+    pub fn new(
+        ui: &mut Ui,
+        name: &str,
+        border: NineGridTexture,
+        title: impl Widget + 'static,
+        close: Button,
+        content: impl Widget + 'static,
+    ) -> Self {
+        // the title bar sits on the top border, the content inside the rest
+        let padding = Edges {
+            left: border.left_width as f32,
+            top: Self::TITLE_TOP,
+            right: border.right_width as f32,
+            bottom: border.bottom_height as f32,
+        };
+        let title_height = border.top_height as f32 - Self::TITLE_TOP;
+
+        let frame = ui.add(None, Rect::default(), Frame::new(border));
+        ui.set_layout(
+            frame,
+            FlexView {
+                direction: Direction::Column,
+                padding,
+                ..Default::default()
+            },
+        );
+        let title_bar = ui.add(Some(frame), Rect::default(), Panel);
+        ui.set_layout(
+            title_bar,
+            FlexView {
+                padding: Edges {
+                    left: 4.0,
+                    ..Default::default()
+                },
+                align: Align::Center,
+                ..Default::default()
+            },
+        );
+        ui.set_flex(
+            title_bar,
+            FlexItem {
+                height: Some(title_height),
+                ..Default::default()
+            },
+        );
+        ui.set_drag_handle(title_bar, frame);
+        let title = ui.add(Some(title_bar), Rect::default(), title);
+        // the title is part of the handle
+        ui.set_drag_handle(title, frame);
+        let spacer = ui.add(Some(title_bar), Rect::default(), Panel);
+        ui.set_flex(spacer, FlexItem::grow(1.0));
+        ui.set_drag_handle(spacer, frame);
+        let close = ui.add(Some(title_bar), Rect::default(), close);
+        ui.on_click(close, move |ui| ui.set_visible(frame, false));
+        let content = ui.add(Some(frame), Rect::default(), content);
+
+        Self {
+            name: name.to_string(),
+            frame,
+            title_bar,
+            close,
+            content,
+            home: None,
+        }
+    }
+
+    /// Adds `button` to the title bar, left of the close button.
+    pub fn add_button(&self, ui: &mut Ui, button: Button) -> WidgetId {
+        let index = ui
+            .children(self.title_bar)
+            .iter()
+            .position(|child| *child == self.close)
+            .unwrap_or(usize::MAX);
+        ui.insert(Some(self.title_bar), index, Rect::default(), button)
+    }
+
+    /// Shows or hides the window, bringing it to the front when shown.
+    pub fn toggle(&self, ui: &mut Ui) {
+        let visible = !ui.visible(self.frame);
+        ui.set_visible(self.frame, visible);
+        if visible {
+            ui.raise(self.frame);
+        }
+    }
+
+    /// Sizes the window to its content, placing it where it was last left
+    /// or at its home the first time.
+    pub fn fit(&self, ui: &mut Ui, renderer: &Renderer, view: Vec2, positions: &WindowPositions) {
+        let size = ui.measure(self.frame, renderer);
+        let rect = ui.rect(self.frame);
+        let position = if rect.size == Vec2::ZERO {
+            positions
+                .get(&self.name)
+                .or(self.home)
+                .unwrap_or((view - size) / 2.0)
+        } else {
+            rect.position
+        };
+        ui.set_rect(self.frame, Rect::new(position, size));
+    }
+}
+
+/// Where windows were left, kept in a file between sessions as lines of
+/// `name=x,y`.
+pub struct WindowPositions {
+    path: PathBuf,
+    positions: HashMap<String, Vec2>,
+}
+
+impl WindowPositions {
+    /// Starts empty when there's no such file or it doesn't parse.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let positions = std::fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let (name, position) = line.split_once('=')?;
+                let (x, y) = position.split_once(',')?;
+                let position = vec2(x.trim().parse().ok()?, y.trim().parse().ok()?);
+                Some((name.trim().to_string(), position))
+            })
+            .collect();
+        Self { path, positions }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Vec2> {
+        self.positions.get(name).copied()
+    }
+
+    /// Records `window`'s position, returning whether it moved.
+    pub fn update(&mut self, ui: &Ui, window: &Window) -> bool {
+        let position = ui.rect(window.frame).position;
+        let previous = self.positions.insert(window.name.clone(), position);
+        previous != Some(position)
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut names: Vec<&String> = self.positions.keys().collect();
+        names.sort();
+        let text: String = names
+            .into_iter()
+            .map(|name| {
+                let position = self.positions[name];
+                format!("{name}={},{}\n", position.x, position.y)
+            })
+            .collect();
+        std::fs::write(&self.path, text)
+    }
+}
@@ -0,0 +1,228 @@
+use glam::{vec2, Vec2};
+use std::sync::Arc;
+
+use super::{Button, Rect, TooltipContent, Ui, UiEvent, Widget, WidgetId};
+use crate::map::names::MapNames;
+use crate::map::world_map::{Item, Link, WorldMap};
+use crate::sdl::Renderer;
+use crate::sprite::{Sprite, SpriteAnimation};
+use crate::wz::Node;
+
+/// The world map's content, centered in the widget. Clicking a link opens
+/// the world map it leads to, and hovering a spot shows its maps and path.
+/// See [`WorldMapView::add_back_button`] for going back.
+pub struct WorldMapView {
+    root: Node,
+    pub world_map: WorldMap,
+    // the world maps clicked through to get here, for going back
+    history: Vec<WorldMap>,
+    // by `Item::type`
+    spots: Vec<Sprite>,
+    marker: Option<SpriteAnimation>,
+    names: Arc<MapNames>,
+    /// The map the player is on, marked on its spot.
+    pub current_map: i32,
+    // relative to the widget
+    mouse: Option<Vec2>,
+    // as last drawn, for hit tests outside of drawing
+    size: Vec2,
+}
+
+impl WorldMapView {
+    // the back button's corner, from the widget's
+    const BACK: Vec2 = vec2(8.0, 8.0);
+
+    pub fn new(root: &Node, world_map: WorldMap, names: Arc<MapNames>) -> Self {
+        let helper = root.at_path("Map/MapHelper.img/worldMap").ok();
+        let helper = |name: &str| helper.as_ref()?.try_get(name);
+        Self {
+            root: root.clone(),
+            world_map,
+            history: vec![],
+            spots: helper("mapImage").map(Into::into).unwrap_or_default(),
+            marker: helper("curPos").map(Into::into),
+            names,
+            current_map: 0,
+            mouse: None,
+            size: Vec2::ZERO,
+        }
+    }
+
+    /// Opens the world map `name`, keeping the current one to go back to.
+    pub fn open(&mut self, name: &str) {
+        let Ok(world_map) = WorldMap::new(&self.root, name) else {
+            return;
+        };
+        let previous = std::mem::replace(&mut self.world_map, world_map);
+        self.history.push(previous);
+    }
+
+    /// Goes back to the world map this one was opened from, or else up to
+    /// its parent.
+    pub fn back(&mut self) {
+        if let Some(previous) = self.history.pop() {
+            self.world_map = previous;
+            return;
+        }
+        let parent = self.world_map.parent_map.clone();
+        if let Some(world_map) = parent.and_then(|name| WorldMap::new(&self.root, &name).ok()) {
+            self.world_map = world_map;
+        }
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.history.is_empty() || self.world_map.parent_map.is_some()
+    }
+
+    /// Adds the back button over the top-left corner of `view`, enabled
+    /// while there's somewhere to go back to.
+    pub fn add_back_button(ui: &mut Ui, root: &Node, view: WidgetId) -> Option<WidgetId> {
+        let button: Button = root.at_path("UI/UIWindow.img/WorldMap/BtBack").ok()?.into();
+        let size = button.size();
+        let back = ui.add(Some(view), Rect::new(Self::BACK, size), button);
+        let update = move |ui: &mut Ui| {
+            let enabled = ui
+                .get_mut::<WorldMapView>(view)
+                .is_some_and(|view| view.can_go_back());
+            ui.set_enabled(back, enabled);
+        };
+        update(ui);
+        ui.on_click(back, move |ui| {
+            if let Some(view) = ui.get_mut::<WorldMapView>(view) {
+                view.back();
+            }
+            update(ui);
+        });
+        // clicking a link may open a map to come back from
+        ui.on_click(view, update);
+        Some(back)
+    }
+
+    // `point` and `center` relative to the widget
+    fn link_at(&self, center: Vec2, point: Vec2) -> Option<&Link> {
+        self.world_map.map_link.values().find_map(|item| {
+            let lt = center - item.link_img.origin;
+            let rb = lt + item.link_img.size;
+            if !(point.cmpge(lt).all() && point.cmplt(rb).all()) {
+                return None;
+            }
+            let pt = point - lt;
+            let pixel = item
+                .link_img
+                .image
+                .as_rgba8()?
+                .get_pixel(pt.x as u32, pt.y as u32);
+            (pixel.0[3] > 0).then_some(item)
+        })
+    }
+
+    fn spot(&self, item: &Item) -> Option<&Sprite> {
+        self.spots.get(item.r#type as usize).or(self.spots.first())
+    }
+
+    fn spot_at(&self, center: Vec2, point: Vec2) -> Option<&Item> {
+        // the last drawn is on top
+        self.world_map.map_list.values().rev().find(|item| {
+            self.spot(item).is_some_and(|sprite| {
+                let lt = center + item.spot - sprite.origin;
+                point.cmpge(lt).all() && point.cmplt(lt + sprite.size).all()
+            })
+        })
+    }
+
+    // the spot's title and its maps' names, then its description
+    fn spot_tooltip(&self, item: &Item) -> TooltipContent {
+        let mut content = TooltipContent::default();
+        if let Some(sprite) = self.spot(item) {
+            content = content.icon(sprite.clone());
+        }
+        if let Some(title) = &item.title {
+            content = content.text(title, TooltipContent::TITLE);
+        }
+        for id in item.map_no.values() {
+            if let Some(name) = self.names.get(*id) {
+                content = content.line().text(
+                    &format!("{} : {}", name.street, name.map),
+                    TooltipContent::BODY,
+                );
+            }
+        }
+        if let Some(desc) = &item.desc {
+            content = content.line().text(desc, TooltipContent::NOTE);
+        }
+        content
+    }
+}
+
+impl Widget for WorldMapView {
+    fn draw(&mut self, renderer: &mut Renderer, rect: Rect) {
+        self.size = rect.size;
+        let center = rect.size / 2.0;
+        renderer.draw(&self.world_map.base_img, rect.position + center);
+
+        if let Some(mouse) = self.mouse {
+            if let Some(link) = self.link_at(center, mouse) {
+                renderer.draw(&link.link_img, rect.position + center);
+            }
+            if let Some(path) = self
+                .spot_at(center, mouse)
+                .and_then(|item| item.path.as_ref())
+            {
+                renderer.draw(path, rect.position + center);
+            }
+        }
+
+        for item in self.world_map.map_list.values() {
+            if let Some(sprite) = self.spot(item) {
+                renderer.draw(sprite, rect.position + center + item.spot);
+            }
+        }
+
+        let current = self.world_map.spot_of(self.current_map);
+        if let (Some(item), Some(marker)) = (current, &self.marker) {
+            if let Some(sprite) = marker.frames.get(marker.timer.index) {
+                renderer.draw(sprite, rect.position + center + item.spot);
+            }
+        }
+    }
+
+    fn measure(&self, _renderer: &Renderer) -> Vec2 {
+        self.world_map.base_img.size
+    }
+
+    fn tooltip(&self) -> Option<TooltipContent> {
+        let mouse = self.mouse?;
+        let center = self.size / 2.0;
+        if let Some(item) = self.spot_at(center, mouse) {
+            return Some(self.spot_tooltip(item));
+        }
+        let link = self.link_at(center, mouse)?;
+        let text = link.tool_tip.as_ref()?;
+        Some(TooltipContent::default().text(text, TooltipContent::BODY))
+    }
+
+    fn event(&mut self, event: &UiEvent) {
+        match *event {
+            UiEvent::Move(point) => self.mouse = Some(point),
+            UiEvent::Leave => self.mouse = None,
+            UiEvent::Release {
+                point,
+                inside: true,
+            } => {
+                let link = self
+                    .link_at(self.size / 2.0, point)
+                    .map(|link| link.link_map.clone());
+                if let Some(name) = link {
+                    self.open(&name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn tick(&mut self, delta: f32) {
+        if let Some(marker) = &mut self.marker {
+            marker.tick(delta);
+        }
+    }
+}
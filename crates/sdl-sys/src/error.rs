@@ -0,0 +1,29 @@
+use std::ffi::{c_int, CStr};
+use std::fmt;
+
+/// The message SDL reported through `SDL_GetError` when a call failed.
+#[derive(Debug, Clone)]
+pub struct Error(pub String);
+
+impl Error {
+    pub fn last() -> Self {
+        let message = unsafe { CStr::from_ptr(crate::SDL_GetError()) };
+        Self(message.to_string_lossy().into_owned())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SDL error: {}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub(crate) fn check(code: c_int) -> Result<(), Error> {
+    if code < 0 {
+        Err(Error::last())
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use std::ffi::{c_char, c_int, CStr};
+use std::mem::MaybeUninit;
+
+use crate::{SDL_Event, SDL_EventType, SDL_Keycode, SDL_Scancode, Sdl};
+
+fn text(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The subset of `SDL_Event` the client reacts to, anything else ends up in
+/// [`Event::Other`] with its raw type.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Quit,
+    KeyDown {
+        scancode: SDL_Scancode::Type,
+        keycode: SDL_Keycode,
+        repeat: bool,
+    },
+    KeyUp {
+        scancode: SDL_Scancode::Type,
+        keycode: SDL_Keycode,
+    },
+    MouseMotion {
+        x: f32,
+        y: f32,
+    },
+    MouseButtonDown {
+        button: u8,
+        x: f32,
+        y: f32,
+    },
+    MouseButtonUp {
+        button: u8,
+        x: f32,
+        y: f32,
+    },
+    MouseWheel {
+        x: f32,
+        y: f32,
+    },
+    TextInput {
+        text: String,
+    },
+    TextEditing {
+        text: String,
+        start: i32,
+        length: i32,
+    },
+    WindowResized {
+        width: i32,
+        height: i32,
+    },
+    WindowPixelSizeChanged {
+        width: i32,
+        height: i32,
+    },
+    WindowDisplayScaleChanged,
+    Other(u32),
+}
+
+impl From<SDL_Event> for Event {
+    fn from(event: SDL_Event) -> Self {
+        // the union field read in each arm is the one SDL documents for the
+        // event type checked
+        unsafe {
+            match event.type_ as SDL_EventType::Type {
+                SDL_EventType::SDL_EVENT_QUIT => Event::Quit,
+                SDL_EventType::SDL_EVENT_KEY_DOWN => Event::KeyDown {
+                    scancode: event.key.scancode,
+                    keycode: event.key.key,
+                    repeat: event.key.repeat != 0,
+                },
+                SDL_EventType::SDL_EVENT_KEY_UP => Event::KeyUp {
+                    scancode: event.key.scancode,
+                    keycode: event.key.key,
+                },
+                SDL_EventType::SDL_EVENT_MOUSE_MOTION => Event::MouseMotion {
+                    x: event.motion.x,
+                    y: event.motion.y,
+                },
+                SDL_EventType::SDL_EVENT_MOUSE_BUTTON_DOWN => Event::MouseButtonDown {
+                    button: event.button.button,
+                    x: event.button.x,
+                    y: event.button.y,
+                },
+                SDL_EventType::SDL_EVENT_MOUSE_BUTTON_UP => Event::MouseButtonUp {
+                    button: event.button.button,
+                    x: event.button.x,
+                    y: event.button.y,
+                },
+                SDL_EventType::SDL_EVENT_MOUSE_WHEEL => Event::MouseWheel {
+                    x: event.wheel.x,
+                    y: event.wheel.y,
+                },
+                SDL_EventType::SDL_EVENT_TEXT_INPUT => Event::TextInput {
+                    text: text(event.text.text),
+                },
+                SDL_EventType::SDL_EVENT_TEXT_EDITING => Event::TextEditing {
+                    text: text(event.edit.text),
+                    start: event.edit.start,
+                    length: event.edit.length,
+                },
+                SDL_EventType::SDL_EVENT_WINDOW_RESIZED => Event::WindowResized {
+                    width: event.window.data1,
+                    height: event.window.data2,
+                },
+                SDL_EventType::SDL_EVENT_WINDOW_PIXEL_SIZE_CHANGED => {
+                    Event::WindowPixelSizeChanged {
+                        width: event.window.data1,
+                        height: event.window.data2,
+                    }
+                }
+                SDL_EventType::SDL_EVENT_WINDOW_DISPLAY_SCALE_CHANGED => {
+                    Event::WindowDisplayScaleChanged
+                }
+                other => Event::Other(other),
+            }
+        }
+    }
+}
+
+pub struct EventPump<'sdl> {
+    event: MaybeUninit<SDL_Event>,
+    _sdl: &'sdl Sdl,
+}
+
+impl<'sdl> EventPump<'sdl> {
+    pub fn new(sdl: &'sdl Sdl) -> Self {
+        Self {
+            event: MaybeUninit::uninit(),
+            _sdl: sdl,
+        }
+    }
+}
+
+impl Iterator for EventPump<'_> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            if crate::SDL_PollEvent(self.event.as_mut_ptr()) == 1 {
+                Some(self.event.assume_init().into())
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// SDL's key state array, which stays valid for the lifetime of the app.
+#[derive(Clone, Copy)]
+pub struct KeyboardState {
+    state: &'static [u8],
+}
+
+impl KeyboardState {
+    pub fn pressed(&self, scancode: SDL_Scancode::Type) -> bool {
+        self.state
+            .get(scancode as usize)
+            .is_some_and(|state| *state != 0)
+    }
+}
+
+pub fn keyboard_state() -> KeyboardState {
+    let mut len: c_int = 0;
+    let state = unsafe {
+        let ptr = crate::SDL_GetKeyboardState(&mut len);
+        // before SDL is initialised there's no state
+        if ptr.is_null() {
+            &[]
+        } else {
+            std::slice::from_raw_parts(ptr, len.max(0) as usize)
+        }
+    };
+    KeyboardState { state }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MouseState {
+    pub buttons: u32,
+    pub x: f32,
+    pub y: f32,
+}
+
+pub fn mouse_state() -> MouseState {
+    let (mut x, mut y) = (0.0, 0.0);
+    let buttons = unsafe { crate::SDL_GetMouseState(&mut x, &mut y) };
+    MouseState { buttons, x, y }
+}
+
+pub fn ticks() -> u64 {
+    unsafe { crate::SDL_GetTicks() }
+}
+
+pub fn delay(ms: u32) {
+    unsafe { crate::SDL_Delay(ms) }
+}
@@ -3,3 +3,15 @@
 #![allow(non_snake_case)]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+mod error;
+mod event;
+mod render;
+mod video;
+
+pub use error::Error;
+pub use event::{
+    delay, keyboard_state, mouse_state, ticks, Event, EventPump, KeyboardState, MouseState,
+};
+pub use render::{Renderer, Texture};
+pub use video::{Sdl, Window};
@@ -0,0 +1,292 @@
+use std::marker::PhantomData;
+
+use crate::error::check;
+use crate::{
+    Error, SDL_BlendMode, SDL_FPoint, SDL_FRect, SDL_FlipMode, SDL_PixelFormat, SDL_Renderer,
+    SDL_RendererLogicalPresentation, SDL_ScaleMode, SDL_Texture, SDL_TextureAccess, SDL_Vertex,
+};
+
+pub struct Renderer<'w> {
+    raw: *mut SDL_Renderer,
+    _window: PhantomData<&'w ()>,
+}
+
+impl<'w> Renderer<'w> {
+    /// # Safety
+    ///
+    /// `raw` must be a live renderer whose window outlives `'w`; it is
+    /// destroyed when the returned value is dropped.
+    pub unsafe fn from_raw(raw: *mut SDL_Renderer) -> Self {
+        Self {
+            raw,
+            _window: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> *mut SDL_Renderer {
+        self.raw
+    }
+
+    pub fn set_vsync(&self, vsync: i32) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetRenderVSync(self.raw, vsync) })
+    }
+
+    pub fn set_scale(&self, x: f32, y: f32) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetRenderScale(self.raw, x, y) })
+    }
+
+    /// Renders at `width` x `height` whatever the output size, fitting the
+    /// result to the window according to `mode`.
+    pub fn set_logical_presentation(
+        &self,
+        width: i32,
+        height: i32,
+        mode: SDL_RendererLogicalPresentation::Type,
+        scale_mode: SDL_ScaleMode::Type,
+    ) -> Result<(), Error> {
+        check(unsafe {
+            crate::SDL_SetRenderLogicalPresentation(self.raw, width, height, mode, scale_mode)
+        })
+    }
+
+    /// Maps a point in window coordinates, like the mouse position, to
+    /// rendering coordinates.
+    pub fn coordinates_from_window(&self, x: f32, y: f32) -> Result<(f32, f32), Error> {
+        let (mut render_x, mut render_y) = (0.0, 0.0);
+        check(unsafe {
+            crate::SDL_RenderCoordinatesFromWindow(self.raw, x, y, &mut render_x, &mut render_y)
+        })?;
+        Ok((render_x, render_y))
+    }
+
+    pub fn coordinates_to_window(&self, x: f32, y: f32) -> Result<(f32, f32), Error> {
+        let (mut window_x, mut window_y) = (0.0, 0.0);
+        check(unsafe {
+            crate::SDL_RenderCoordinatesToWindow(self.raw, x, y, &mut window_x, &mut window_y)
+        })?;
+        Ok((window_x, window_y))
+    }
+
+    pub fn set_draw_color(&self, r: u8, g: u8, b: u8, a: u8) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetRenderDrawColor(self.raw, r, g, b, a) })
+    }
+
+    pub fn set_draw_blend_mode(&self, mode: SDL_BlendMode) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetRenderDrawBlendMode(self.raw, mode) })
+    }
+
+    pub fn clear(&self) -> Result<(), Error> {
+        check(unsafe { crate::SDL_RenderClear(self.raw) })
+    }
+
+    pub fn present(&self) -> Result<(), Error> {
+        check(unsafe { crate::SDL_RenderPresent(self.raw) })
+    }
+
+    pub fn fill_rect(&self, rect: &SDL_FRect) -> Result<(), Error> {
+        check(unsafe { crate::SDL_RenderFillRect(self.raw, rect) })
+    }
+
+    pub fn draw_line(&self, x1: f32, y1: f32, x2: f32, y2: f32) -> Result<(), Error> {
+        check(unsafe { crate::SDL_RenderLine(self.raw, x1, y1, x2, y2) })
+    }
+
+    pub fn draw_rect(&self, rect: &SDL_FRect) -> Result<(), Error> {
+        check(unsafe { crate::SDL_RenderRect(self.raw, rect) })
+    }
+
+    /// Creates a static texture from tightly packed RGBA8 pixels.
+    pub fn create_texture(
+        &self,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Result<Texture<'_>, Error> {
+        assert_eq!(pixels.len(), (width * height * 4) as usize);
+        let raw = unsafe {
+            crate::SDL_CreateTexture(
+                self.raw,
+                SDL_PixelFormat::SDL_PIXELFORMAT_ABGR8888,
+                SDL_TextureAccess::SDL_TEXTUREACCESS_STATIC,
+                width as i32,
+                height as i32,
+            )
+        };
+        if raw.is_null() {
+            return Err(Error::last());
+        }
+        // owned from here on so the texture is destroyed on early returns
+        let texture = Texture {
+            raw,
+            width,
+            height,
+            _renderer: PhantomData,
+        };
+        check(unsafe {
+            crate::SDL_UpdateTexture(
+                raw,
+                std::ptr::null(),
+                pixels.as_ptr() as *const core::ffi::c_void,
+                width as i32 * 4,
+            )
+        })?;
+        texture.set_scale_mode(SDL_ScaleMode::SDL_SCALEMODE_NEAREST)?;
+        texture.set_blend_mode(crate::SDL_BLENDMODE_BLEND)?;
+        Ok(texture)
+    }
+
+    pub fn copy(
+        &self,
+        texture: &Texture,
+        src: Option<&SDL_FRect>,
+        dst: &SDL_FRect,
+        angle: f64,
+        center: Option<&SDL_FPoint>,
+        flip: SDL_FlipMode::Type,
+    ) -> Result<(), Error> {
+        check(unsafe {
+            crate::SDL_RenderTextureRotated(
+                self.raw,
+                texture.raw,
+                src.map_or(std::ptr::null(), |src| src as *const SDL_FRect),
+                dst,
+                angle,
+                center.map_or(std::ptr::null(), |center| center as *const SDL_FPoint),
+                flip,
+            )
+        })
+    }
+
+    /// Draws `texture` scaled into `dst`, stretching only the middle and
+    /// keeping the given border widths intact.
+    #[allow(clippy::too_many_arguments)]
+    pub fn copy_9grid(
+        &self,
+        texture: &Texture,
+        src: Option<&SDL_FRect>,
+        left: f32,
+        right: f32,
+        top: f32,
+        bottom: f32,
+        scale: f32,
+        dst: &SDL_FRect,
+    ) -> Result<(), Error> {
+        check(unsafe {
+            crate::SDL_RenderTexture9Grid(
+                self.raw,
+                texture.raw,
+                src.map_or(std::ptr::null(), |src| src as *const SDL_FRect),
+                left,
+                right,
+                top,
+                bottom,
+                scale,
+                dst,
+            )
+        })
+    }
+
+    /// Draws triangles, `indices` taking three vertices at a time.
+    pub fn geometry(
+        &self,
+        texture: Option<&Texture>,
+        vertices: &[SDL_Vertex],
+        indices: &[i32],
+    ) -> Result<(), Error> {
+        check(unsafe {
+            crate::SDL_RenderGeometry(
+                self.raw,
+                texture.map_or(std::ptr::null_mut(), |texture| texture.raw),
+                vertices.as_ptr(),
+                vertices.len() as i32,
+                indices.as_ptr(),
+                indices.len() as i32,
+            )
+        })
+    }
+
+    /// Reads back the current render target as tightly packed RGBA8 pixels.
+    pub fn read_pixels(&self) -> Result<(u32, u32, Vec<u8>), Error> {
+        let surface = unsafe { crate::SDL_RenderReadPixels(self.raw, std::ptr::null()) };
+        if surface.is_null() {
+            return Err(Error::last());
+        }
+        let converted = unsafe {
+            let converted =
+                crate::SDL_ConvertSurface(surface, SDL_PixelFormat::SDL_PIXELFORMAT_ABGR8888);
+            crate::SDL_DestroySurface(surface);
+            converted
+        };
+        if converted.is_null() {
+            return Err(Error::last());
+        }
+        let result = unsafe {
+            let surface = &*converted;
+            let (width, height) = (surface.w as usize, surface.h as usize);
+            let mut pixels = Vec::with_capacity(width * height * 4);
+            for row in 0..height {
+                pixels.extend_from_slice(std::slice::from_raw_parts(
+                    (surface.pixels as *const u8).add(row * surface.pitch as usize),
+                    width * 4,
+                ));
+            }
+            crate::SDL_DestroySurface(converted);
+            (width as u32, height as u32, pixels)
+        };
+        Ok(result)
+    }
+}
+
+impl Drop for Renderer<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            crate::SDL_DestroyRenderer(self.raw);
+        }
+    }
+}
+
+/// A texture that can't outlive the renderer that created it.
+pub struct Texture<'r> {
+    raw: *mut SDL_Texture,
+    width: u32,
+    height: u32,
+    _renderer: PhantomData<&'r ()>,
+}
+
+impl Texture<'_> {
+    pub fn raw(&self) -> *mut SDL_Texture {
+        self.raw
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn set_alpha_mod(&self, alpha: u8) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetTextureAlphaMod(self.raw, alpha) })
+    }
+
+    pub fn set_color_mod(&self, r: u8, g: u8, b: u8) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetTextureColorMod(self.raw, r, g, b) })
+    }
+
+    pub fn set_blend_mode(&self, mode: SDL_BlendMode) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetTextureBlendMode(self.raw, mode) })
+    }
+
+    pub fn set_scale_mode(&self, mode: SDL_ScaleMode::Type) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetTextureScaleMode(self.raw, mode) })
+    }
+}
+
+impl Drop for Texture<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            crate::SDL_DestroyTexture(self.raw);
+        }
+    }
+}
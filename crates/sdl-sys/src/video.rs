@@ -0,0 +1,120 @@
+use std::ffi::CString;
+use std::marker::PhantomData;
+
+use crate::error::check;
+use crate::{Error, Renderer, SDL_Rect, SDL_Window};
+
+/// Keeps SDL initialised; everything else borrows from it. Not `Send`, SDL's
+/// video subsystem has to stay on the thread that initialised it.
+pub struct Sdl {
+    _marker: PhantomData<*mut ()>,
+}
+
+impl Sdl {
+    pub fn init(flags: u32) -> Result<Self, Error> {
+        check(unsafe { crate::SDL_Init(flags) })?;
+        Ok(Self {
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl Drop for Sdl {
+    fn drop(&mut self) {
+        unsafe {
+            crate::SDL_Quit();
+        }
+    }
+}
+
+pub struct Window<'sdl> {
+    raw: *mut SDL_Window,
+    _sdl: PhantomData<&'sdl Sdl>,
+}
+
+impl<'sdl> Window<'sdl> {
+    pub fn new(
+        _sdl: &'sdl Sdl,
+        title: &str,
+        width: i32,
+        height: i32,
+        flags: u64,
+    ) -> Result<Self, Error> {
+        let title = CString::new(title).map_err(|err| Error(err.to_string()))?;
+        let raw = unsafe { crate::SDL_CreateWindow(title.as_ptr(), width, height, flags) };
+        if raw.is_null() {
+            return Err(Error::last());
+        }
+        Ok(Self {
+            raw,
+            _sdl: PhantomData,
+        })
+    }
+
+    pub fn raw(&self) -> *mut SDL_Window {
+        self.raw
+    }
+
+    pub fn pixel_density(&self) -> f32 {
+        unsafe { crate::SDL_GetWindowPixelDensity(self.raw) }
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        let (mut width, mut height) = (0, 0);
+        unsafe {
+            crate::SDL_GetWindowSize(self.raw, &mut width, &mut height);
+        }
+        (width, height)
+    }
+
+    pub fn set_size(&self, width: i32, height: i32) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetWindowSize(self.raw, width, height) })
+    }
+
+    /// Borderless fullscreen on the window's display, which keeps the desktop
+    /// mode.
+    pub fn set_fullscreen(&self, fullscreen: bool) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetWindowFullscreen(self.raw, fullscreen as crate::SDL_bool) })
+    }
+
+    pub fn set_bordered(&self, bordered: bool) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetWindowBordered(self.raw, bordered as crate::SDL_bool) })
+    }
+
+    /// Starts sending [`crate::Event::TextInput`] and
+    /// [`crate::Event::TextEditing`], bringing up the IME where there is one.
+    pub fn start_text_input(&self) -> Result<(), Error> {
+        check(unsafe { crate::SDL_StartTextInput(self.raw) })
+    }
+
+    pub fn stop_text_input(&self) -> Result<(), Error> {
+        check(unsafe { crate::SDL_StopTextInput(self.raw) })
+    }
+
+    pub fn text_input_active(&self) -> bool {
+        unsafe { crate::SDL_TextInputActive(self.raw) != 0 }
+    }
+
+    /// Where text is being typed, in window coordinates, so the IME can put
+    /// its candidate list next to it. `cursor` is the caret's offset from
+    /// `rect.x`.
+    pub fn set_text_input_area(&self, rect: &SDL_Rect, cursor: i32) -> Result<(), Error> {
+        check(unsafe { crate::SDL_SetTextInputArea(self.raw, rect, cursor) })
+    }
+
+    pub fn create_renderer(&self) -> Result<Renderer<'_>, Error> {
+        let raw = unsafe { crate::SDL_CreateRenderer(self.raw, std::ptr::null()) };
+        if raw.is_null() {
+            return Err(Error::last());
+        }
+        Ok(unsafe { Renderer::from_raw(raw) })
+    }
+}
+
+impl Drop for Window<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            crate::SDL_DestroyWindow(self.raw);
+        }
+    }
+}